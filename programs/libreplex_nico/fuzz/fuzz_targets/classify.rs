@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libreplex_nico_fuzz::{classify, NicoInput};
+
+fuzz_target!(|input: NicoInput| {
+    classify(&input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libreplex_nico_fuzz::{transfer_params, NicoInput};
+
+fuzz_target!(|input: NicoInput| {
+    transfer_params(&input);
+});
@@ -0,0 +1,284 @@
+// Shared inputs for the fuzz targets. The account data is arbitrary, but
+// keys and owners are mostly drawn from the programs and derived addresses
+// Nico looks for, so that inputs get past the owner and address checks and
+// into the deserialization and account resolution behind them.
+//
+// Both targets only check that nothing panics: every result, error or not,
+// is discarded.
+
+use arbitrary::Arbitrary;
+use libreplex_nico::{
+    associated_token_address, edition_address, metadata_address, token_record_address,
+    transfer_pnft::{NicoPnftBumps, TransferPnftParams},
+    CompressedLeaf, MetadataType, Nico, NicoSharedAccounts, NicoTransferParams, NicoType,
+    AUTH_RULES_PROGRAM_ID,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey, system_program, sysvar};
+
+fn programs() -> [Pubkey; 13] {
+    [
+        system_program::ID,
+        nifty_asset::ID,
+        mpl_core::ID,
+        spl_token::ID,
+        spl_token_2022::ID,
+        mpl_token_metadata::ID,
+        spl_associated_token_account::ID,
+        mpl_bubblegum::ID,
+        SPL_ACCOUNT_COMPRESSION_ID,
+        SPL_NOOP_ID,
+        sysvar::instructions::ID,
+        AUTH_RULES_PROGRAM_ID,
+        Pubkey::default(),
+    ]
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum FuzzKey {
+    Program(u8),
+    // derived from the keys of earlier accounts, by index
+    Metadata(u8),
+    Edition(u8),
+    TokenRecord(u8, u8),
+    AssociatedToken {
+        wallet: u8,
+        mint: u8,
+        token_2022: bool,
+    },
+    Raw([u8; 32]),
+}
+
+impl FuzzKey {
+    fn resolve(self, earlier: &[Pubkey]) -> Pubkey {
+        let programs = programs();
+        let pick = |index: u8| {
+            earlier
+                .get(index as usize % earlier.len().max(1))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        match self {
+            FuzzKey::Program(index) => programs[index as usize % programs.len()],
+            FuzzKey::Metadata(mint) => metadata_address(&pick(mint)),
+            FuzzKey::Edition(mint) => edition_address(&pick(mint)),
+            FuzzKey::TokenRecord(mint, token_account) => {
+                token_record_address(&pick(mint), &pick(token_account))
+            }
+            FuzzKey::AssociatedToken {
+                wallet,
+                mint,
+                token_2022,
+            } => {
+                let token_program = if token_2022 {
+                    spl_token_2022::ID
+                } else {
+                    spl_token::ID
+                };
+                associated_token_address(&pick(wallet), &pick(mint), &token_program)
+            }
+            FuzzKey::Raw(bytes) => Pubkey::new_from_array(bytes),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzAccount {
+    pub key: FuzzKey,
+    pub owner: FuzzKey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzLeaf {
+    pub owner: [u8; 32],
+    pub delegate: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+}
+
+/// The accounts are both the classification inputs, picked by index, and
+/// the remaining accounts, in the order given. The first account is the
+/// asset, and the merkle tree when a leaf is given.
+#[derive(Arbitrary, Debug)]
+pub struct NicoInput {
+    pub accounts: Vec<FuzzAccount>,
+    pub metadata: Option<u8>,
+    pub current_owner: Option<u8>,
+    pub current_token_account: Option<u8>,
+    pub payer: u8,
+    pub recipient: u8,
+    pub authority: Option<u8>,
+    // tree config index, proof start index and the leaf
+    pub compressed: Option<(u8, u8, FuzzLeaf)>,
+    pub shared_accounts: bool,
+    pub bumps: Option<[u8; 5]>,
+}
+
+struct AccountStore {
+    keys: Vec<Pubkey>,
+    owners: Vec<Pubkey>,
+    lamports: Vec<u64>,
+    data: Vec<Vec<u8>>,
+    flags: Vec<(bool, bool)>,
+}
+
+impl AccountStore {
+    fn new(accounts: &[FuzzAccount]) -> AccountStore {
+        let mut keys = Vec::with_capacity(accounts.len());
+        let mut owners = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            owners.push(account.owner.resolve(&keys));
+            keys.push(account.key.resolve(&keys));
+        }
+
+        AccountStore {
+            keys,
+            owners,
+            lamports: accounts.iter().map(|x| x.lamports).collect(),
+            data: accounts.iter().map(|x| x.data.clone()).collect(),
+            flags: accounts
+                .iter()
+                .map(|x| (x.is_signer, x.is_writable))
+                .collect(),
+        }
+    }
+
+    fn infos(&mut self) -> Vec<AccountInfo<'_>> {
+        self.keys
+            .iter()
+            .zip(self.owners.iter())
+            .zip(self.lamports.iter_mut())
+            .zip(self.data.iter_mut())
+            .zip(self.flags.iter())
+            .map(
+                |((((key, owner), lamports), data), (is_signer, is_writable))| {
+                    AccountInfo::new(
+                        key,
+                        *is_signer,
+                        *is_writable,
+                        lamports,
+                        data,
+                        owner,
+                        false,
+                        0,
+                    )
+                },
+            )
+            .collect()
+    }
+}
+
+fn pick<'a>(infos: &'a [AccountInfo<'a>], index: u8) -> &'a AccountInfo<'a> {
+    &infos[index as usize % infos.len()]
+}
+
+fn classify_accounts<'a>(input: &NicoInput, infos: &'a [AccountInfo<'a>]) -> Option<Nico<'a>> {
+    if let Some((tree_config, proof_start, leaf)) = &input.compressed {
+        let proof_start = (*proof_start as usize).min(infos.len());
+        let leaf = CompressedLeaf {
+            owner: Pubkey::new_from_array(leaf.owner),
+            delegate: Pubkey::new_from_array(leaf.delegate),
+            nonce: leaf.nonce,
+            index: leaf.index,
+            root: leaf.root,
+            data_hash: leaf.data_hash,
+            creator_hash: leaf.creator_hash,
+        };
+        return Nico::from_compressed(
+            &infos[0],
+            pick(infos, *tree_config),
+            &infos[proof_start..],
+            leaf,
+        )
+        .ok();
+    }
+
+    Nico::try_from_accounts(
+        &infos[0],
+        input.metadata.map(|x| pick(infos, x)),
+        input.current_owner.map(|x| pick(infos, x)),
+        input.current_token_account.map(|x| pick(infos, x)),
+    )
+    .ok()
+}
+
+/// Classify arbitrary accounts.
+pub fn classify(input: &NicoInput) {
+    if input.accounts.is_empty() {
+        return;
+    }
+    let mut store = AccountStore::new(&input.accounts);
+    let infos = store.infos();
+
+    classify_accounts(input, &infos);
+}
+
+/// Classify arbitrary accounts and resolve the transfer params of the
+/// resulting Nico from the same accounts.
+pub fn transfer_params(input: &NicoInput) {
+    if input.accounts.is_empty() {
+        return;
+    }
+    let mut store = AccountStore::new(&input.accounts);
+    let infos = store.infos();
+
+    resolve_transfer_params(input, &infos);
+}
+
+fn resolve_transfer_params<'a>(input: &NicoInput, infos: &'a [AccountInfo<'a>]) {
+    let Some(nico) = classify_accounts(input, infos) else {
+        return;
+    };
+
+    let Ok(mut params) = NicoTransferParams::new(
+        &nico,
+        pick(infos, input.payer),
+        pick(infos, input.recipient),
+        input.authority.map(|x| pick(infos, x)),
+        &[],
+        infos,
+    ) else {
+        return;
+    };
+    if input.shared_accounts {
+        params.shared_accounts = NicoSharedAccounts::resolve(infos);
+    }
+
+    let asset_info = &infos[0];
+    let _ = nico.transfer_params(asset_info, &params, infos);
+
+    // the bumps are only taken by programs building the pNFT CPI themselves
+    if let (
+        Some([metadata, edition, target, source_record, target_record]),
+        NicoType::Mint {
+            metadata: MetadataType::Mxprogrammable,
+            current_owner: Some(current_owner),
+            current_token_account: Some(current_token_account),
+        },
+    ) = (input.bumps, &nico.nico_type)
+    {
+        let bumps = NicoPnftBumps {
+            metadata,
+            edition,
+            target_token_account: target,
+            source_token_record: source_record,
+            target_token_record: target_record,
+        };
+        let _ = TransferPnftParams::from_nico_transfer_params_with_bumps(
+            asset_info,
+            current_owner,
+            current_token_account,
+            &params,
+            bumps,
+            infos,
+        );
+    }
+}
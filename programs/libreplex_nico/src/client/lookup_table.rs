@@ -0,0 +1,130 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        AddressLookupTableAccount,
+    },
+    clock::Slot,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, CompileError},
+    pubkey::Pubkey,
+    system_program,
+};
+
+use crate::{MetadataType, Nico, NicoType, AUTH_RULES_PROGRAM_ID};
+
+// keeps each extend instruction well inside a legacy transaction
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 30;
+
+/// Accounts that stay the same across trades of the given Nicos: program
+/// ids, `AUTH_RULES`, sysvar instructions, the rule sets and the groups.
+pub fn static_accounts(nicos: &[Nico], rule_sets: &[Pubkey]) -> Vec<Pubkey> {
+    let mut addresses = vec![system_program::ID];
+
+    for nico in nicos {
+        match &nico.nico_type {
+            NicoType::Nifty => addresses.push(nifty_asset::ID),
+            NicoType::MxCore => addresses.push(mpl_core::ID),
+            NicoType::Compressed { .. } => {
+                addresses.extend([mpl_bubblegum::ID, SPL_NOOP_ID, SPL_ACCOUNT_COMPRESSION_ID])
+            }
+            NicoType::Mint { metadata, .. } => {
+                addresses.extend([nico.owner_program, spl_associated_token_account::ID]);
+                if *metadata != MetadataType::Token22Extension {
+                    addresses.extend([
+                        mpl_token_metadata::ID,
+                        sysvar_instructions_program::ID,
+                        AUTH_RULES_PROGRAM_ID,
+                    ]);
+                }
+            }
+        }
+        if let Some(group) = nico.group {
+            addresses.push(group);
+        }
+    }
+    addresses.extend_from_slice(rule_sets);
+
+    // keep the first occurrence so the table order is stable
+    let mut unique: Vec<Pubkey> = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        if !unique.contains(&address) {
+            unique.push(address);
+        }
+    }
+    unique
+}
+
+/// Instructions to create and fill an address lookup table, and the table
+/// itself once they have landed.
+pub struct NicoLookupTablePlan {
+    pub lookup_table: Pubkey,
+    pub addresses: Vec<Pubkey>,
+    pub create_instruction: Instruction,
+    // send each in its own transaction, after the create instruction
+    pub extend_instructions: Vec<Instruction>,
+}
+
+impl NicoLookupTablePlan {
+    pub fn new(
+        authority: Pubkey,
+        payer: Pubkey,
+        recent_slot: Slot,
+        addresses: Vec<Pubkey>,
+    ) -> NicoLookupTablePlan {
+        let (create_instruction, lookup_table) = create_lookup_table(authority, payer, recent_slot);
+
+        let extend_instructions = addresses
+            .chunks(MAX_ADDRESSES_PER_EXTEND)
+            .map(|chunk| extend_lookup_table(lookup_table, authority, Some(payer), chunk.to_vec()))
+            .collect();
+
+        NicoLookupTablePlan {
+            lookup_table,
+            addresses,
+            create_instruction,
+            extend_instructions,
+        }
+    }
+
+    /// Plan a table holding the static accounts of the given Nicos.
+    pub fn for_nicos(
+        authority: Pubkey,
+        payer: Pubkey,
+        recent_slot: Slot,
+        nicos: &[Nico],
+        rule_sets: &[Pubkey],
+    ) -> NicoLookupTablePlan {
+        NicoLookupTablePlan::new(
+            authority,
+            payer,
+            recent_slot,
+            static_accounts(nicos, rule_sets),
+        )
+    }
+
+    pub fn lookup_table_account(&self) -> AddressLookupTableAccount {
+        AddressLookupTableAccount {
+            key: self.lookup_table,
+            addresses: self.addresses.clone(),
+        }
+    }
+
+    /// Compile the trade instructions into a v0 message that loads the
+    /// static accounts from the table.
+    pub fn compile_message(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        recent_blockhash: Hash,
+    ) -> Result<v0::Message, CompileError> {
+        v0::Message::try_compile(
+            payer,
+            instructions,
+            &[self.lookup_table_account()],
+            recent_blockhash,
+        )
+    }
+}
@@ -0,0 +1,57 @@
+pub mod lookup_table;
+pub use lookup_table::*;
+
+pub mod remaining_accounts;
+pub use remaining_accounts::*;
+
+use mpl_token_metadata::{accounts::Metadata, types::ProgrammableConfig};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+
+/// Account fetched over RPC. Lets clients classify a Nico with the same
+/// code the program runs.
+#[derive(Clone, Debug)]
+pub struct AccountSnapshot {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+impl AccountSnapshot {
+    pub fn account_info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.pubkey,
+            false,
+            false,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+/// Token auth rule set of a pNFT, read from its metadata account.
+pub fn rule_set(metadata: &AccountSnapshot) -> Option<Pubkey> {
+    Metadata::safe_deserialize(&metadata.data)
+        .ok()
+        .and_then(|x| x.programmable_config)
+        .and_then(|x| match x {
+            ProgrammableConfig::V1 { rule_set } => rule_set,
+        })
+}
+
+/// Metadata account a Token-2022 mint points to, which may be the mint
+/// itself. The program classifies the mint with it.
+pub fn metadata_pointer(mint: &AccountSnapshot) -> Option<Pubkey> {
+    StateWithExtensions::<Mint>::unpack(&mint.data)
+        .ok()?
+        .get_extension::<MetadataPointer>()
+        .ok()
+        .and_then(|x| Option::<Pubkey>::from(x.metadata_address))
+}
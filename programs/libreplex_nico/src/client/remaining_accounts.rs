@@ -0,0 +1,86 @@
+use mpl_token_metadata::accounts::{MasterEdition, Metadata, TokenRecord};
+use solana_program::{instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    associated_token_address_and_bump, transfer_pnft::NicoPnftBumps, Error, Nico,
+    NicoAccountLayout, NicoStandard, NicoType,
+};
+
+// The account lists come from the NicoAccountLayout of each operation, so
+// they are in the order the program checks them.
+
+/// Remaining accounts for [`Nico::transfer`]. `rule_set` is the token auth
+/// rule set of a pNFT, see [`crate::client::rule_set`]. For compressed Nicos
+/// the tree config, merkle tree and proof come last, so the proof can be
+/// sliced off the end of the remaining accounts.
+pub fn transfer_remaining_accounts(
+    nico: &Nico,
+    recipient: &Pubkey,
+    authority: &Pubkey,
+    rule_set: Option<Pubkey>,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    NicoAccountLayout::transfer(nico, recipient, authority, rule_set).map(|x| x.account_metas())
+}
+
+/// Bumps for [`crate::transfer_pnft::TransferPnftParams::from_nico_transfer_params_with_bumps`],
+/// so that the program does not have to search for them.
+pub fn pnft_transfer_bumps(nico: &Nico, recipient: &Pubkey) -> Result<NicoPnftBumps, ProgramError> {
+    let source_token_account = match &nico.nico_type {
+        NicoType::Mint {
+            current_token_account: Some(x),
+            ..
+        } => *x.key,
+        _ => return Err(Error::MissingCurrentTokenAccount.into()),
+    };
+    let (target_token_account, target_token_account_bump) =
+        associated_token_address_and_bump(recipient, &nico.pubkey, &nico.owner_program);
+
+    Ok(NicoPnftBumps {
+        metadata: Metadata::find_pda(&nico.pubkey).1,
+        edition: MasterEdition::find_pda(&nico.pubkey).1,
+        target_token_account: target_token_account_bump,
+        source_token_record: TokenRecord::find_pda(&nico.pubkey, &source_token_account).1,
+        target_token_record: TokenRecord::find_pda(&nico.pubkey, &target_token_account).1,
+    })
+}
+
+/// Remaining accounts for [`Nico::burn`].
+pub fn burn_remaining_accounts(nico: &Nico) -> Result<Vec<AccountMeta>, ProgramError> {
+    NicoAccountLayout::burn(nico).map(|x| x.account_metas())
+}
+
+/// Remaining accounts for [`Nico::lock`] and [`Nico::unlock`].
+pub fn lock_remaining_accounts(
+    nico: &Nico,
+    rule_set: Option<Pubkey>,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    NicoAccountLayout::lock(nico, rule_set).map(|x| x.account_metas())
+}
+
+/// Remaining accounts for [`Nico::approve_delegate`] and
+/// [`Nico::revoke_delegate`].
+pub fn delegate_remaining_accounts(
+    nico: &Nico,
+    rule_set: Option<Pubkey>,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    NicoAccountLayout::delegate(nico, rule_set).map(|x| x.account_metas())
+}
+
+/// Remaining accounts for [`Nico::create`]. `asset` is the new asset or
+/// mint account.
+pub fn create_remaining_accounts(
+    standard: NicoStandard,
+    asset: &Pubkey,
+    owner: &Pubkey,
+    group: Option<Pubkey>,
+) -> Vec<AccountMeta> {
+    NicoAccountLayout::create(standard, asset, owner, group).account_metas()
+}
+
+/// Remaining accounts for [`Nico::join_group`].
+pub fn join_group_remaining_accounts(
+    nico: &Nico,
+    group: &Pubkey,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    NicoAccountLayout::join_group(nico, group).map(|x| x.account_metas())
+}
@@ -67,8 +67,67 @@ pub enum Error {
     InvalidNiftyAsset,
     /// 19 - Token program not specified in remaining accounts
     #[error("No token program in remaining accounts")]
-    NoTokenProgramInRemainingAccounts
-
+    NoTokenProgramInRemainingAccounts,
+    /// 20 - Unsupported token standard
+    #[error("Unsupported token standard")]
+    UnsupportedTokenStandard,
+    /// 21 - Missing metadata account
+    #[error("Missing metadata account")]
+    MissingMetadataAccount,
+    /// 22 - Unexpected asset owner
+    #[error("Unexpected asset owner")]
+    UnexpectedAssetOwner,
+    /// 23 - Asset deserialization failed
+    #[error("Asset deserialization failed")]
+    AssetDeserializationFailed,
+    /// 24 - Missing remaining account
+    #[error("Missing remaining account")]
+    MissingRemainingAccount,
+    /// 25 - Missing current owner
+    #[error("Nico was constructed without current owner")]
+    MissingCurrentOwner,
+    /// 26 - Missing current token account
+    #[error("Nico was constructed without current token account")]
+    MissingCurrentTokenAccount,
+    /// 27 - Invalid transfer authority
+    #[error("Authority is neither the owner nor the delegate of the token account")]
+    InvalidTransferAuthority,
+    /// 28 - Insufficient accounts to classify
+    #[error("Insufficient accounts to classify the token standard")]
+    InsufficientAccountsToClassify,
+    /// 29 - Unsupported delegate role
+    #[error("Delegate role not supported for this asset type")]
+    UnsupportedDelegateRole,
+    /// 30 - Not in group
+    #[error("Asset is not a member of the expected group")]
+    NotInGroup,
+    /// 31 - Invalid group
+    #[error("Group account is not a valid collection")]
+    InvalidGroup,
+    /// 32 - Not owned by
+    #[error("Asset is not owned by the expected wallet")]
+    NotOwnedBy,
+    /// 33 - Invalid token account
+    #[error("Token account does not hold the asset")]
+    InvalidTokenAccount,
+    /// 34 - Asset frozen
+    #[error("Asset is frozen")]
+    AssetFrozen,
+    /// 35 - Recipient count mismatch
+    #[error("Expected one recipient, or one recipient per asset")]
+    RecipientCountMismatch,
+    /// 36 - Invalid bump
+    #[error("Bump does not give a valid program address")]
+    InvalidBump,
+    /// 37 - Unused remaining accounts
+    #[error("Remaining accounts not used by the operation")]
+    UnusedRemainingAccounts,
+    /// 38 - Unsupported royalties
+    #[error("Royalties not supported for this asset type")]
+    UnsupportedRoyalties,
+    /// 39 - Invalid metadata account
+    #[error("Metadata account does not belong to the mint")]
+    InvalidMetadataAccount,
 }
 
 impl PrintProgramError for Error {
@@ -0,0 +1,290 @@
+use std::{collections::BTreeSet, ops::Deref};
+
+use anchor_lang::{
+    error::ErrorCode, Accounts, AccountsExit, Key, Result, ToAccountInfos, ToAccountMetas,
+};
+use solana_program::{
+    account_info::AccountInfo, instruction::AccountMeta, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions},
+    state::{Account as TokenAccount, Mint},
+};
+
+use crate::{metadata_address, Nico};
+
+/// A Nico in an Anchor accounts struct. The asset is classified when the
+/// accounts are deserialized and the wrapper derefs to the [`Nico`], so it
+/// can be constrained like any other account:
+///
+/// ```ignore
+/// use libreplex_nico::*;
+///
+/// #[derive(Accounts)]
+/// pub struct Stake<'info> {
+///     #[account(constraint = nico.group == Some(collection.key()))]
+///     pub nico: NicoAccount<'info>,
+///
+///     /// CHECK: compared against the group of the Nico
+///     pub collection: UncheckedAccount<'info>,
+/// }
+/// ```
+///
+/// A mint is classified with the metadata, token account and owner found
+/// among the accounts that follow it, other fields or remaining accounts.
+/// Compressed Nicos are not a single account and cannot be wrapped.
+///
+/// Anchor treats the wrapper as a nested accounts struct: only `constraint`
+/// is supported on it, and the glob import brings in the modules the derive
+/// expects next to the type.
+pub struct NicoAccount<'info> {
+    info: &'info AccountInfo<'info>,
+    nico: Nico<'info>,
+}
+
+impl<'info> NicoAccount<'info> {
+    /// Classify `info`, looking up the accounts a mint needs in `accounts`.
+    pub fn try_from(
+        info: &'info AccountInfo<'info>,
+        accounts: &'info [AccountInfo<'info>],
+    ) -> Result<NicoAccount<'info>> {
+        let (metadata, current_owner, current_token_account) =
+            if info.owner == &spl_token::ID || info.owner == &spl_token_2022::ID {
+                let token_account = find_token_account(info, accounts);
+                let owner = token_account
+                    .and_then(token_account_owner)
+                    .and_then(|owner| accounts.iter().find(|x| x.key.eq(&owner)));
+                (find_metadata(info, accounts), owner, token_account)
+            } else {
+                (None, None, None)
+            };
+
+        let nico = Nico::try_from_accounts(info, metadata, current_owner, current_token_account)
+            .map_err(ProgramError::from)?;
+
+        Ok(NicoAccount { info, nico })
+    }
+
+    pub fn into_inner(self) -> Nico<'info> {
+        self.nico
+    }
+}
+
+// the metaplex metadata, or the account the metadata pointer of a token-2022
+// mint names
+fn find_metadata<'info>(
+    mint_info: &'info AccountInfo<'info>,
+    accounts: &'info [AccountInfo<'info>],
+) -> Option<&'info AccountInfo<'info>> {
+    let metadata_pointer = mint_info.try_borrow_data().ok().and_then(|data| {
+        StateWithExtensions::<Mint>::unpack(&data)
+            .ok()?
+            .get_extension::<MetadataPointer>()
+            .ok()
+            .and_then(|x| Option::<Pubkey>::from(x.metadata_address))
+    });
+    let metaplex_metadata = metadata_address(mint_info.key);
+
+    accounts
+        .iter()
+        .find(|x| metadata_pointer.is_some_and(|pointer| x.key.eq(&pointer)))
+        .or_else(|| accounts.iter().find(|x| x.key.eq(&metaplex_metadata)))
+}
+
+fn find_token_account<'info>(
+    mint_info: &'info AccountInfo<'info>,
+    accounts: &'info [AccountInfo<'info>],
+) -> Option<&'info AccountInfo<'info>> {
+    accounts
+        .iter()
+        .filter(|x| x.owner.eq(mint_info.owner))
+        .find(|x| {
+            x.try_borrow_data().is_ok_and(|data| {
+                StateWithExtensions::<TokenAccount>::unpack(&data)
+                    .is_ok_and(|x| x.base.mint == *mint_info.key && x.base.amount == 1)
+            })
+        })
+}
+
+fn token_account_owner(token_account_info: &AccountInfo) -> Option<Pubkey> {
+    let data = token_account_info.try_borrow_data().ok()?;
+    StateWithExtensions::<TokenAccount>::unpack(&data)
+        .ok()
+        .map(|x| x.base.owner)
+}
+
+/// Bumps of a [`NicoAccount`]. It has none, Anchor needs the type.
+#[derive(Debug, Default)]
+pub struct NicoAccountBumps {}
+
+impl<'info, B> Accounts<'info, B> for NicoAccount<'info> {
+    fn try_accounts(
+        _program_id: &Pubkey,
+        accounts: &mut &'info [AccountInfo<'info>],
+        _ix_data: &[u8],
+        _bumps: &mut B,
+        _reallocs: &mut BTreeSet<Pubkey>,
+    ) -> Result<Self> {
+        if accounts.is_empty() {
+            return Err(ErrorCode::AccountNotEnoughKeys.into());
+        }
+        let info = &accounts[0];
+        *accounts = &accounts[1..];
+        NicoAccount::try_from(info, accounts)
+    }
+}
+
+// the Nico programs write the asset, the wrapper has nothing to persist
+impl<'info> AccountsExit<'info> for NicoAccount<'info> {}
+
+// always writable, like the client and CPI accounts below
+impl<'info> ToAccountMetas for NicoAccount<'info> {
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        vec![AccountMeta::new(
+            *self.info.key,
+            is_signer.unwrap_or(self.info.is_signer),
+        )]
+    }
+}
+
+impl<'info> ToAccountInfos<'info> for NicoAccount<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![self.info.clone()]
+    }
+}
+
+impl<'info> AsRef<AccountInfo<'info>> for NicoAccount<'info> {
+    fn as_ref(&self) -> &AccountInfo<'info> {
+        self.info
+    }
+}
+
+impl<'info> Deref for NicoAccount<'info> {
+    type Target = Nico<'info>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.nico
+    }
+}
+
+impl<'info> Key for NicoAccount<'info> {
+    fn key(&self) -> Pubkey {
+        *self.info.key
+    }
+}
+
+#[cfg(feature = "idl-build")]
+impl<'info> NicoAccount<'info> {
+    pub fn __anchor_private_gen_idl_accounts(
+        _accounts: &mut std::collections::HashMap<
+            String,
+            anchor_lang::anchor_syn::idl::types::IdlTypeDefinition,
+        >,
+        _defined_types: &mut std::collections::HashMap<
+            String,
+            anchor_lang::anchor_syn::idl::types::IdlTypeDefinition,
+        >,
+    ) -> Vec<anchor_lang::anchor_syn::idl::types::IdlAccountItem> {
+        use anchor_lang::anchor_syn::idl::types::{IdlAccount, IdlAccountItem};
+
+        vec![IdlAccountItem::IdlAccount(IdlAccount {
+            name: "asset".into(),
+            is_mut: true,
+            is_signer: false,
+            is_optional: None,
+            docs: None,
+            pda: None,
+            relations: vec![],
+        })]
+    }
+}
+
+// What `#[derive(Accounts)]` would generate for the wrapper: the accounts
+// structs of clients building instructions and of programs calling over CPI.
+// Accounts structs embedding a NicoAccount refer to them by these names.
+
+#[doc(hidden)]
+pub mod __client_accounts_nico_account {
+    use anchor_lang::{prelude::borsh, AnchorSerialize, ToAccountMetas};
+    use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+    /// Generated client accounts for [`super::NicoAccount`].
+    #[derive(AnchorSerialize)]
+    pub struct NicoAccount {
+        pub asset: Pubkey,
+    }
+
+    impl ToAccountMetas for NicoAccount {
+        fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+            vec![AccountMeta::new(self.asset, false)]
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __cpi_client_accounts_nico_account {
+    use anchor_lang::{ToAccountInfos, ToAccountMetas};
+    use solana_program::{account_info::AccountInfo, instruction::AccountMeta};
+
+    /// Generated CPI struct of the accounts for [`super::NicoAccount`].
+    pub struct NicoAccount<'info> {
+        pub asset: AccountInfo<'info>,
+    }
+
+    impl<'info> ToAccountMetas for NicoAccount<'info> {
+        fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+            vec![AccountMeta::new(*self.asset.key, false)]
+        }
+    }
+
+    impl<'info> ToAccountInfos<'info> for NicoAccount<'info> {
+        fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+            vec![self.asset.clone()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::*;
+
+    use crate::{testing::fake_nifty_asset, *};
+
+    // the struct from the docs of NicoAccount
+    #[derive(Accounts)]
+    pub struct Stake<'info> {
+        #[account(constraint = nico.group == Some(collection.key()))]
+        pub nico: NicoAccount<'info>,
+
+        /// CHECK: compared against the group of the Nico
+        pub collection: UncheckedAccount<'info>,
+    }
+
+    fn try_stake(accounts: &'static [AccountInfo<'static>]) -> Result<Stake<'static>> {
+        Stake::try_accounts(
+            &Pubkey::new_unique(),
+            &mut &accounts[..],
+            &[],
+            &mut StakeBumps::default(),
+            &mut Default::default(),
+        )
+    }
+
+    #[test]
+    fn constrains_the_group() {
+        let collection = fake_nifty_asset(&Pubkey::new_unique(), None);
+        let nico = fake_nifty_asset(&Pubkey::new_unique(), Some(collection.key));
+
+        let stake = try_stake(Box::leak(Box::new([nico.clone(), collection.clone()]))).unwrap();
+        assert_eq!(stake.nico.key(), *nico.key);
+        assert_eq!(
+            stake.nico.to_account_metas(None),
+            vec![AccountMeta::new(*nico.key, false)]
+        );
+
+        let other = fake_nifty_asset(&Pubkey::new_unique(), None);
+        let error = try_stake(Box::leak(Box::new([nico.clone(), other.clone()]))).err();
+        assert_eq!(error, Some(ErrorCode::ConstraintRaw.into()));
+    }
+}
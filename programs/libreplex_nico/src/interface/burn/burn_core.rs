@@ -0,0 +1,77 @@
+use mpl_core::instructions::{BurnV1Cpi as MplCoreBurnCpi, BurnV1InstructionArgs};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, NicoBurnParams};
+
+pub struct BurnCoreParams<'a, 'b> {
+    pub mpl_core_program_info: &'a AccountInfo<'a>,
+    pub authority_info: Option<&'a AccountInfo<'a>>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub collection_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> BurnCoreParams<'a, 'b> {
+    pub fn from_nico_burn_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoBurnParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<BurnCoreParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params
+                .accounts
+                .find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_core_program_info =
+            params
+                .accounts
+                .find(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(BurnCoreParams {
+            mpl_core_program_info,
+            authority_info: params.authority_info,
+            asset_info,
+            // assets in a collection must pass the collection to burn
+            collection_asset_opt_info: params.group_asset_opt_info,
+            signer_seeds: params.signer_seeds,
+            payer_info: params.payer_info,
+            system_program_info,
+        })
+    }
+}
+
+pub fn check_and_burn_core(params: BurnCoreParams<'_, '_>) -> ProgramResult {
+    let BurnCoreParams {
+        mpl_core_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        system_program_info,
+        collection_asset_opt_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("mpl_core_program", mpl_core_program_info, &mpl_core::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    MplCoreBurnCpi {
+        __program: mpl_core_program_info,
+        asset: asset_info,
+        collection: collection_asset_opt_info,
+        payer: payer_info,
+        authority: authority_info,
+        system_program: Some(system_program_info),
+        log_wrapper: None,
+        __args: BurnV1InstructionArgs {
+            compression_proof: None,
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_token_metadata::instructions::{BurnV1Cpi, BurnV1InstructionArgs};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{
+    assertions::assert_same_pubkeys, edition_address, metadata_address, token_record_address,
+    NicoBurnParams,
+};
+
+pub struct BurnMetaplexParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    token_account_info: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    // only present for programmable non-fungibles
+    token_record_info: Option<&'a AccountInfo<'a>>,
+    // only present for members of a verified collection
+    collection_metadata_info: Option<&'a AccountInfo<'a>>,
+    sysvar_instruction_info: &'a AccountInfo<'a>,
+    spl_token_program_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> BurnMetaplexParams<'a, 'b> {
+    pub fn from_nico_burn_params(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        programmable: bool,
+        params: &NicoBurnParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<BurnMetaplexParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params
+                .accounts
+                .find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_token_program_info = params.accounts.find(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let spl_token_program_info = params.accounts.find(
+            &params.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+
+        let sysvar_instruction_info = params.accounts.find(
+            &sysvar_instructions_program::ID,
+            remaining_accounts,
+            "sysvar_instructions_program",
+        )?;
+
+        let metadata_info = params.accounts.find(
+            &metadata_address(&params.nico_pubkey),
+            remaining_accounts,
+            "metadata",
+        )?;
+
+        let edition_info = params.accounts.find(
+            &edition_address(&params.nico_pubkey),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        let token_record_info = if programmable {
+            Some(params.accounts.find(
+                &token_record_address(&params.nico_pubkey, current_token_account.key),
+                remaining_accounts,
+                "token_record",
+            )?)
+        } else {
+            None
+        };
+
+        // burning a member of a sized collection decrements the collection size
+        let collection_metadata_info = params
+            .group_asset_opt_info
+            .map(|group| {
+                params.accounts.find(
+                    &metadata_address(group.key),
+                    remaining_accounts,
+                    "collection_metadata",
+                )
+            })
+            .transpose()?;
+
+        Ok(BurnMetaplexParams {
+            mpl_token_program_info,
+            authority_info: params.authority_info.map_or(params.payer_info, |x| x),
+            asset_info: nico_asset_info,
+            system_program_info,
+            token_account_info: current_token_account,
+            metadata: metadata_info,
+            edition: edition_info,
+            token_record_info,
+            collection_metadata_info,
+            sysvar_instruction_info,
+            spl_token_program_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_burn_metaplex(params: BurnMetaplexParams<'_, '_>) -> ProgramResult {
+    let BurnMetaplexParams {
+        mpl_token_program_info,
+        authority_info,
+        asset_info,
+        system_program_info,
+        token_account_info,
+        metadata,
+        edition,
+        token_record_info,
+        collection_metadata_info,
+        sysvar_instruction_info,
+        spl_token_program_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    BurnV1Cpi {
+        __program: mpl_token_program_info,
+        authority: authority_info,
+        collection_metadata: collection_metadata_info,
+        metadata,
+        edition: Some(edition),
+        mint: asset_info,
+        token: token_account_info,
+        master_edition: None,
+        master_edition_mint: None,
+        master_edition_token: None,
+        edition_marker: None,
+        token_record: token_record_info,
+        system_program: system_program_info,
+        sysvar_instructions: sysvar_instruction_info,
+        spl_token_program: spl_token_program_info,
+        __args: BurnV1InstructionArgs { amount: 1 },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
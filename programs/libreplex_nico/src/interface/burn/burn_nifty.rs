@@ -0,0 +1,66 @@
+use nifty_asset::{instructions::BurnCpi as NiftyBurnCpi, types::Standard as NiftyStandard};
+use nifty_asset_types::state::Asset;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+
+use crate::{assertions::assert_same_pubkeys, Error, NicoBurnParams};
+
+pub struct BurnNiftyParams<'a, 'b> {
+    pub nifty_program_info: &'a AccountInfo<'a>,
+    pub signer_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub group_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> BurnNiftyParams<'a, 'b> {
+    pub fn from_nico_burn_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoBurnParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<BurnNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info =
+            params.accounts.find(&nifty_asset::ID, remaining_accounts, "nifty_asset")?;
+        Ok(BurnNiftyParams {
+            nifty_program_info,
+            signer_info: params.authority_info.map_or(params.payer_info, |x| x),
+            asset_info,
+            group_asset_opt_info: params.group_asset_opt_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_burn_nifty(params: BurnNiftyParams<'_, '_>) -> ProgramResult {
+    let BurnNiftyParams {
+        nifty_program_info,
+        signer_info,
+        asset_info,
+        group_asset_opt_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("nifty_program", nifty_program_info, &nifty_asset::ID)?;
+
+    let data = asset_info.try_borrow_data()?;
+
+    // Must have the expected amount of data and the correct discriminator and standard.
+    if data.len() < Asset::LEN || data[2] != NiftyStandard::NonFungible as u8 {
+        return Err(Error::InvalidNiftyAsset.into());
+    }
+
+    // Drop the data reference before the CPI.
+    drop(data);
+
+    NiftyBurnCpi {
+        __program: nifty_program_info,
+        asset: asset_info,
+        signer: signer_info,
+        // rent is refunded to the signer
+        recipient: None,
+        group: group_asset_opt_info,
+    }
+    .invoke_signed(signer_seeds)?;
+    Ok(())
+}
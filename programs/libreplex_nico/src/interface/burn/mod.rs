@@ -0,0 +1,169 @@
+pub mod burn_core;
+pub mod burn_metaplex;
+pub mod burn_nifty;
+
+use burn_core::{check_and_burn_core, BurnCoreParams};
+use burn_metaplex::{check_and_burn_metaplex, BurnMetaplexParams};
+use burn_nifty::{check_and_burn_nifty, BurnNiftyParams};
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg};
+
+use crate::{Error, MetadataType, Nico, NicoParams, NicoType};
+
+pub type NicoBurnParams<'a, 'b> = NicoParams<'a, 'b>;
+
+impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Burn the asset. The authority (or the payer if no authority is given)
+    /// must be the owner or a delegate allowed to burn.
+    pub fn burn(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        let params = NicoBurnParams::new(self, payer, authority, signer_seeds, remaining_accounts)?;
+
+        self.burn_with_params(asset_info, params, remaining_accounts)
+    }
+
+    /// Same as [`Nico::burn`], with the params built by the caller, e.g. to
+    /// set the accounts parsed by [`crate::NicoAccountLayout::parse`].
+    pub fn burn_with_params(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        params: NicoBurnParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> ProgramResult {
+        match &self.nico_type {
+            NicoType::Nifty => {
+                let nifty_params = BurnNiftyParams::from_nico_burn_params(
+                    asset_info,
+                    &params,
+                    remaining_accounts,
+                )?;
+                check_and_burn_nifty(nifty_params)
+            }
+            NicoType::MxCore => {
+                let core_params =
+                    BurnCoreParams::from_nico_burn_params(asset_info, &params, remaining_accounts)?;
+                check_and_burn_core(core_params)
+            }
+            NicoType::Compressed { .. } => {
+                msg!("Burning compressed Nicos is not supported");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint {
+                metadata,
+                current_token_account,
+                ..
+            } => {
+                let metadata = match metadata {
+                    // token standard not set on the metadata. work it out from the accounts
+                    MetadataType::Unknown => {
+                        MetadataType::infer(asset_info, *current_token_account, remaining_accounts)?
+                    }
+                    x => *x,
+                };
+                match metadata {
+                    MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+                    MetadataType::Token22Extension => {
+                        msg!("Burning Token-2022 metadata extension Nicos is not supported");
+                        Err(Error::UnsupportedAssetType.into())
+                    }
+                    MetadataType::MxNonProgrammable | MetadataType::Mxprogrammable => {
+                        let metaplex_params = BurnMetaplexParams::from_nico_burn_params(
+                            asset_info,
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            metadata == MetadataType::Mxprogrammable,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_burn_metaplex(metaplex_params)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libreplex_shared::sysvar_instructions_program;
+    use mpl_core::types::UpdateAuthority;
+    use mpl_token_metadata::types::TokenStandard;
+    use solana_program::{pubkey::Pubkey, system_program};
+
+    use crate::{
+        edition_address,
+        testing::{
+            fake_account, fake_core_asset, fake_metadata, fake_program, fake_wallet, record_invokes,
+        },
+        token_record_address,
+    };
+
+    use super::*;
+
+    #[test]
+    fn burns_core_asset_with_its_collection() {
+        let collection_info = fake_core_asset(UpdateAuthority::None);
+        let asset_info = fake_core_asset(UpdateAuthority::Collection(*collection_info.key));
+        let nico = Nico::try_from_accounts(asset_info, None, None, None).unwrap();
+        let remaining_accounts = vec![
+            fake_program(&system_program::ID).clone(),
+            fake_program(&mpl_core::ID).clone(),
+            collection_info.clone(),
+        ]
+        .leak();
+
+        let (result, invokes) =
+            record_invokes(|| nico.burn(asset_info, fake_wallet(), None, remaining_accounts, &[]));
+
+        result.unwrap();
+        assert_eq!(invokes[0].program_id, mpl_core::ID);
+        // the collection is account 1 of BurnV1
+        assert_eq!(invokes[0].accounts[1].pubkey, *collection_info.key);
+    }
+
+    #[test]
+    fn burns_token_record_of_pnft_only() {
+        for token_standard in [
+            TokenStandard::NonFungible,
+            TokenStandard::ProgrammableNonFungible,
+        ] {
+            let (mint_info, metadata_info) =
+                fake_metadata(&spl_token::ID, Some(token_standard.clone()), None);
+            let token_account_info = fake_wallet();
+            let nico = Nico::try_from_accounts(
+                mint_info,
+                Some(metadata_info),
+                None,
+                Some(token_account_info),
+            )
+            .unwrap();
+
+            let token_record = token_record_address(mint_info.key, token_account_info.key);
+            let remaining_accounts = vec![
+                fake_program(&system_program::ID).clone(),
+                fake_program(&mpl_token_metadata::ID).clone(),
+                fake_program(&spl_token::ID).clone(),
+                fake_program(&sysvar_instructions_program::ID).clone(),
+                metadata_info.clone(),
+                fake_account(edition_address(mint_info.key), Pubkey::default(), vec![]).clone(),
+                fake_account(token_record, Pubkey::default(), vec![]).clone(),
+            ]
+            .leak();
+
+            let (result, invokes) = record_invokes(|| {
+                nico.burn(mint_info, fake_wallet(), None, remaining_accounts, &[])
+            });
+
+            result.unwrap();
+            assert_eq!(invokes[0].program_id, mpl_token_metadata::ID);
+            assert_eq!(
+                invokes[0].accounts.iter().any(|x| x.pubkey == token_record),
+                token_standard == TokenStandard::ProgrammableNonFungible
+            );
+        }
+    }
+}
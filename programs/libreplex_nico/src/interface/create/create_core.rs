@@ -0,0 +1,218 @@
+use mpl_core::{
+    instructions::{
+        CreateCollectionV1Cpi, CreateCollectionV1InstructionArgs, CreateV1Cpi,
+        CreateV1InstructionArgs,
+    },
+    types::{Creator, DataState, Plugin, PluginAuthorityPair, Royalties, RuleSet},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, find_account, NicoCreateParams, NicoCreator};
+
+pub struct CreateCoreParams<'a, 'b> {
+    pub mpl_core_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub owner_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub collection_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    name: String,
+    uri: String,
+    royalties: u16,
+    creators: Vec<NicoCreator>,
+    // set by as_group when creating a collection
+    is_collection: bool,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> CreateCoreParams<'a, 'b> {
+    pub fn from_nico_create_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoCreateParams<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: &'a AccountInfo<'a>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<CreateCoreParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_core_program_info =
+            find_account(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(CreateCoreParams {
+            mpl_core_program_info,
+            authority_info,
+            asset_info,
+            owner_info: params.owner,
+            payer_info,
+            system_program_info,
+            collection_asset_opt_info: params.group,
+            name: params.name.clone(),
+            uri: params.uri.clone(),
+            royalties: params.royalties,
+            creators: params.creators.clone(),
+            is_collection: false,
+            signer_seeds,
+        })
+    }
+
+    /// Create a collection instead of an asset. Core collections have no
+    /// size cap, so there is no max size to set.
+    pub fn as_group(mut self) -> Self {
+        self.is_collection = true;
+        self
+    }
+}
+
+pub fn check_and_create_core(params: CreateCoreParams<'_, '_>) -> ProgramResult {
+    let CreateCoreParams {
+        mpl_core_program_info,
+        authority_info,
+        asset_info,
+        owner_info,
+        payer_info,
+        system_program_info,
+        collection_asset_opt_info,
+        name,
+        uri,
+        royalties,
+        creators,
+        is_collection,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("mpl_core_program", mpl_core_program_info, &mpl_core::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    let plugins = if royalties > 0 || !creators.is_empty() {
+        Some(vec![PluginAuthorityPair {
+            plugin: Plugin::Royalties(Royalties {
+                basis_points: royalties,
+                creators: creators
+                    .iter()
+                    .map(|x| Creator {
+                        address: x.address,
+                        percentage: x.share,
+                    })
+                    .collect(),
+                rule_set: RuleSet::None,
+            }),
+            authority: None,
+        }])
+    } else {
+        None
+    };
+
+    if is_collection {
+        // collections have no owner, the authority manages them
+        CreateCollectionV1Cpi {
+            __program: mpl_core_program_info,
+            collection: asset_info,
+            update_authority: Some(authority_info),
+            payer: payer_info,
+            system_program: system_program_info,
+            __args: CreateCollectionV1InstructionArgs { name, uri, plugins },
+        }
+        .invoke_signed(signer_seeds)?;
+
+        return Ok(());
+    }
+
+    CreateV1Cpi {
+        __program: mpl_core_program_info,
+        asset: asset_info,
+        collection: collection_asset_opt_info,
+        authority: Some(authority_info),
+        payer: payer_info,
+        owner: Some(owner_info),
+        // core takes the update authority from the collection when there is
+        // one, and rejects an explicit one. without, it defaults to the payer
+        update_authority: collection_asset_opt_info
+            .is_none()
+            .then_some(authority_info),
+        system_program: system_program_info,
+        log_wrapper: None,
+        __args: CreateV1InstructionArgs {
+            data_state: DataState::AccountState,
+            name,
+            uri,
+            plugins,
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_core::types::UpdateAuthority;
+    use solana_program::{instruction::Instruction, pubkey::Pubkey};
+
+    use crate::{
+        testing::{fake_core_asset, fake_program, fake_wallet, record_invokes},
+        NicoStandard,
+    };
+
+    use super::*;
+
+    // the update authority core is asked to set when creating an asset,
+    // where the payer and the authority differ
+    fn create_core(group: Option<&'static AccountInfo<'static>>) -> (Pubkey, Instruction) {
+        let authority_info = fake_wallet();
+        let remaining_accounts = Box::leak(Box::new([
+            fake_program(&system_program::ID).clone(),
+            fake_program(&mpl_core::ID).clone(),
+        ]));
+        let params = NicoCreateParams {
+            standard: NicoStandard::MxCore,
+            name: String::new(),
+            uri: String::new(),
+            royalties: 0,
+            creators: vec![],
+            group,
+            owner: fake_wallet(),
+        };
+
+        let (result, invokes) = record_invokes(|| {
+            check_and_create_core(CreateCoreParams::from_nico_create_params(
+                fake_wallet(),
+                &params,
+                fake_wallet(),
+                authority_info,
+                &[],
+                remaining_accounts,
+            )?)
+        });
+        result.unwrap();
+
+        (*authority_info.key, invokes.into_iter().next().unwrap())
+    }
+
+    fn update_authority(instruction: &Instruction) -> Pubkey {
+        // account 5 of CreateV1
+        instruction.accounts[5].pubkey
+    }
+
+    #[test]
+    fn sets_authority_as_update_authority() {
+        let (authority, instruction) = create_core(None);
+
+        assert_eq!(update_authority(&instruction), authority);
+    }
+
+    #[test]
+    fn leaves_update_authority_to_the_collection() {
+        let collection_info = fake_core_asset(UpdateAuthority::None);
+        let (_, instruction) = create_core(Some(collection_info));
+
+        // absent optional accounts are passed as the program id
+        assert_eq!(update_authority(&instruction), mpl_core::ID);
+    }
+}
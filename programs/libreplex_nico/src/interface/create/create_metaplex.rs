@@ -0,0 +1,286 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_token_metadata::{
+    instructions::{
+        CreateV1Cpi, CreateV1InstructionArgs, MintV1Cpi, MintV1InstructionArgs,
+        VerifyCollectionV1Cpi,
+    },
+    types::{Collection, CollectionDetails, Creator, PrintSupply, TokenStandard},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{
+    assertions::assert_same_pubkeys, associated_token_address, edition_address, find_account,
+    metadata_address, token_record_address, NicoCreateParams, NicoCreator, NicoStandard,
+};
+
+pub struct CreateMetaplexParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub owner_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    target_token_account_info: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    // pNFT only
+    token_record_info: Option<&'a AccountInfo<'a>>,
+    sysvar_instruction_info: &'a AccountInfo<'a>,
+    spl_token_program_info: &'a AccountInfo<'a>,
+    spl_ata_program: &'a AccountInfo<'a>,
+    // collection mint, metadata and master edition
+    group_accounts: Option<[&'a AccountInfo<'a>; 3]>,
+    token_standard: TokenStandard,
+    name: String,
+    uri: String,
+    royalties: u16,
+    creators: Vec<NicoCreator>,
+    // set by as_group when creating a sized collection
+    collection_details: Option<CollectionDetails>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> CreateMetaplexParams<'a, 'b> {
+    pub fn from_nico_create_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoCreateParams<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: &'a AccountInfo<'a>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<CreateMetaplexParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_token_program_info = find_account(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let spl_token_program_info =
+            find_account(&spl_token::ID, remaining_accounts, "token_program")?;
+
+        let spl_ata_program = find_account(
+            &spl_associated_token_account::ID,
+            remaining_accounts,
+            "associated_token_program",
+        )?;
+
+        let sysvar_instruction_info = find_account(
+            &sysvar_instructions_program::ID,
+            remaining_accounts,
+            "sysvar_instructions_program",
+        )?;
+
+        let metadata = find_account(
+            &metadata_address(asset_info.key),
+            remaining_accounts,
+            "metadata",
+        )?;
+
+        let edition = find_account(
+            &edition_address(asset_info.key),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        let target_token_account_info = find_account(
+            &associated_token_address(params.owner.key, asset_info.key, &spl_token::ID),
+            remaining_accounts,
+            "target_ata",
+        )?;
+
+        let token_standard = match params.standard {
+            NicoStandard::Mxprogrammable => TokenStandard::ProgrammableNonFungible,
+            _ => TokenStandard::NonFungible,
+        };
+
+        let token_record_info = match token_standard {
+            TokenStandard::ProgrammableNonFungible => Some(find_account(
+                &token_record_address(asset_info.key, target_token_account_info.key),
+                remaining_accounts,
+                "token_record",
+            )?),
+            _ => None,
+        };
+
+        let group_accounts = params
+            .group
+            .map(|group_info| -> Result<_, ProgramError> {
+                Ok([
+                    group_info,
+                    find_account(
+                        &metadata_address(group_info.key),
+                        remaining_accounts,
+                        "collection_metadata",
+                    )?,
+                    find_account(
+                        &edition_address(group_info.key),
+                        remaining_accounts,
+                        "collection_master_edition",
+                    )?,
+                ])
+            })
+            .transpose()?;
+
+        Ok(CreateMetaplexParams {
+            mpl_token_program_info,
+            authority_info,
+            asset_info,
+            owner_info: params.owner,
+            payer_info,
+            system_program_info,
+            target_token_account_info,
+            metadata,
+            edition,
+            token_record_info,
+            sysvar_instruction_info,
+            spl_token_program_info,
+            spl_ata_program,
+            group_accounts,
+            token_standard,
+            name: params.name.clone(),
+            uri: params.uri.clone(),
+            royalties: params.royalties,
+            creators: params.creators.clone(),
+            collection_details: None,
+            signer_seeds,
+        })
+    }
+
+    /// Create a sized collection NFT instead of a plain NFT. Token metadata
+    /// tracks the size but does not cap it.
+    pub fn as_group(mut self) -> Self {
+        self.collection_details = Some(CollectionDetails::V1 { size: 0 });
+        self
+    }
+}
+
+pub fn check_and_create_metaplex(params: CreateMetaplexParams<'_, '_>) -> ProgramResult {
+    let CreateMetaplexParams {
+        mpl_token_program_info,
+        authority_info,
+        asset_info,
+        owner_info,
+        payer_info,
+        system_program_info,
+        target_token_account_info,
+        metadata,
+        edition,
+        token_record_info,
+        sysvar_instruction_info,
+        spl_token_program_info,
+        spl_ata_program,
+        group_accounts,
+        token_standard,
+        name,
+        uri,
+        royalties,
+        creators,
+        collection_details,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    let creators = if creators.is_empty() {
+        None
+    } else {
+        Some(
+            creators
+                .iter()
+                .map(|x| Creator {
+                    address: x.address,
+                    // only the authority signs, so only it can be verified
+                    verified: x.address.eq(authority_info.key),
+                    share: x.share,
+                })
+                .collect(),
+        )
+    };
+
+    CreateV1Cpi {
+        __program: mpl_token_program_info,
+        metadata,
+        master_edition: Some(edition),
+        mint: (asset_info, true),
+        authority: authority_info,
+        payer: payer_info,
+        update_authority: (authority_info, true),
+        system_program: system_program_info,
+        sysvar_instructions: sysvar_instruction_info,
+        spl_token_program: spl_token_program_info,
+        __args: CreateV1InstructionArgs {
+            name,
+            symbol: String::new(),
+            uri,
+            seller_fee_basis_points: royalties,
+            creators,
+            primary_sale_happened: false,
+            is_mutable: true,
+            token_standard,
+            // added unverified, verified below
+            collection: group_accounts.map(|[group_info, _, _]| Collection {
+                verified: false,
+                key: *group_info.key,
+            }),
+            uses: None,
+            collection_details,
+            rule_set: None,
+            decimals: Some(0),
+            print_supply: Some(PrintSupply::Zero),
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    MintV1Cpi {
+        __program: mpl_token_program_info,
+        token: target_token_account_info,
+        token_owner: Some(owner_info),
+        metadata,
+        master_edition: Some(edition),
+        token_record: token_record_info,
+        mint: asset_info,
+        authority: authority_info,
+        delegate_record: None,
+        payer: payer_info,
+        system_program: system_program_info,
+        sysvar_instructions: sysvar_instruction_info,
+        spl_token_program: spl_token_program_info,
+        spl_ata_program,
+        authorization_rules_program: None,
+        authorization_rules: None,
+        __args: MintV1InstructionArgs {
+            amount: 1,
+            authorization_data: None,
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    if let Some([group_info, group_metadata_info, group_edition_info]) = group_accounts {
+        VerifyCollectionV1Cpi {
+            __program: mpl_token_program_info,
+            authority: authority_info,
+            delegate_record: None,
+            metadata,
+            collection_mint: group_info,
+            collection_metadata: Some(group_metadata_info),
+            collection_master_edition: Some(group_edition_info),
+            system_program: system_program_info,
+            sysvar_instructions: sysvar_instruction_info,
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+
+    Ok(())
+}
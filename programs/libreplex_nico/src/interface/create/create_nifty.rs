@@ -0,0 +1,152 @@
+use nifty_asset::{
+    constraints::EmptyBuilder,
+    extensions::{
+        CreatorsBuilder, ExtensionBuilder, GroupingBuilder, MetadataBuilder, RoyaltiesBuilder,
+    },
+    instructions::{CreateCpi, CreateInstructionArgs},
+    types::{ExtensionInput, ExtensionType, Standard},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, find_account, NicoCreateParams, NicoCreator};
+
+pub struct CreateNiftyParams<'a, 'b> {
+    pub nifty_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub owner_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub group_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    name: String,
+    uri: String,
+    royalties: u16,
+    creators: Vec<NicoCreator>,
+    // set by as_group when creating a group asset
+    grouping: Option<Option<u32>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> CreateNiftyParams<'a, 'b> {
+    pub fn from_nico_create_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoCreateParams<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: &'a AccountInfo<'a>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<CreateNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info = find_account(&nifty_asset::ID, remaining_accounts, "nifty_asset")?;
+
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        Ok(CreateNiftyParams {
+            nifty_program_info,
+            authority_info,
+            asset_info,
+            owner_info: params.owner,
+            payer_info,
+            system_program_info,
+            group_asset_opt_info: params.group,
+            name: params.name.clone(),
+            uri: params.uri.clone(),
+            royalties: params.royalties,
+            creators: params.creators.clone(),
+            grouping: None,
+            signer_seeds,
+        })
+    }
+
+    /// Create a group asset with the grouping extension instead of a
+    /// plain asset.
+    pub fn as_group(mut self, max_size: Option<u32>) -> Self {
+        self.grouping = Some(max_size);
+        self
+    }
+}
+
+fn extension(extension_type: ExtensionType, data: Vec<u8>) -> ExtensionInput {
+    ExtensionInput {
+        extension_type,
+        length: data.len() as u32,
+        data: Some(data),
+    }
+}
+
+pub fn check_and_create_nifty(params: CreateNiftyParams<'_, '_>) -> ProgramResult {
+    let CreateNiftyParams {
+        nifty_program_info,
+        authority_info,
+        asset_info,
+        owner_info,
+        payer_info,
+        system_program_info,
+        group_asset_opt_info,
+        name,
+        uri,
+        royalties,
+        creators,
+        grouping,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("nifty_program", nifty_program_info, &nifty_asset::ID)?;
+
+    let mut extensions = vec![extension(
+        ExtensionType::Metadata,
+        MetadataBuilder::default()
+            .set(None, None, Some(&uri))
+            .data(),
+    )];
+
+    if !creators.is_empty() {
+        let mut builder = CreatorsBuilder::default();
+        for creator in &creators {
+            // only the authority can verify itself, and only once created
+            builder.add(&creator.address, false, creator.share);
+        }
+        extensions.push(extension(ExtensionType::Creators, builder.data()));
+    }
+
+    if royalties > 0 {
+        extensions.push(extension(
+            ExtensionType::Royalties,
+            RoyaltiesBuilder::default()
+                .set(royalties as u64, &mut EmptyBuilder::default())
+                .data(),
+        ));
+    }
+
+    if let Some(max_size) = grouping {
+        extensions.push(extension(
+            ExtensionType::Grouping,
+            GroupingBuilder::default()
+                .set(max_size.map(|x| x as u64), None)
+                .data(),
+        ));
+    }
+
+    CreateCpi {
+        __program: nifty_program_info,
+        asset: asset_info,
+        authority: (authority_info, true),
+        owner: owner_info,
+        group: group_asset_opt_info,
+        group_authority: group_asset_opt_info.map(|_| authority_info),
+        payer: Some(payer_info),
+        system_program: Some(system_program_info),
+        __args: CreateInstructionArgs {
+            name,
+            standard: Standard::NonFungible,
+            mutable: true,
+            extensions: Some(extensions),
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke_signed,
+    program_error::ProgramError, rent::Rent, system_instruction, system_program, sysvar::Sysvar,
+};
+use spl_token_2022::{
+    extension::{group_member_pointer, group_pointer, metadata_pointer, ExtensionType},
+    instruction::{initialize_mint2, mint_to, set_authority, AuthorityType},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::{
+    assertions::assert_same_pubkeys, associated_token_address, find_account, Error,
+    NicoCreateParams,
+};
+
+pub struct CreateToken22Params<'a, 'b> {
+    pub token_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub owner_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    target_token_account_info: &'a AccountInfo<'a>,
+    spl_ata_program: &'a AccountInfo<'a>,
+    // token-2022 mint holding the TokenGroup extension
+    group_mint_opt_info: Option<&'a AccountInfo<'a>>,
+    name: String,
+    uri: String,
+    // set by as_group when creating a group mint
+    group_max_size: Option<u32>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> CreateToken22Params<'a, 'b> {
+    pub fn from_nico_create_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoCreateParams<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: &'a AccountInfo<'a>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<CreateToken22Params<'a, 'b>, ProgramError> {
+        // token-2022 has no royalty standard to write them to
+        if params.royalties > 0 || !params.creators.is_empty() {
+            msg!("Royalties and creators are not supported on token-2022 assets");
+            return Err(Error::UnsupportedRoyalties.into());
+        }
+
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let token_program_info =
+            find_account(&spl_token_2022::ID, remaining_accounts, "token_program")?;
+
+        let spl_ata_program = find_account(
+            &spl_associated_token_account::ID,
+            remaining_accounts,
+            "associated_token_program",
+        )?;
+
+        let target_token_account_info = find_account(
+            &associated_token_address(params.owner.key, asset_info.key, &spl_token_2022::ID),
+            remaining_accounts,
+            "target_ata",
+        )?;
+
+        Ok(CreateToken22Params {
+            token_program_info,
+            authority_info,
+            asset_info,
+            owner_info: params.owner,
+            payer_info,
+            system_program_info,
+            target_token_account_info,
+            spl_ata_program,
+            group_mint_opt_info: params.group,
+            name: params.name.clone(),
+            uri: params.uri.clone(),
+            group_max_size: None,
+            signer_seeds,
+        })
+    }
+
+    /// Create a group mint with the token group extension instead of a
+    /// member. Without a max size the group is capped at `u32::MAX`.
+    pub fn as_group(mut self, max_size: Option<u32>) -> Self {
+        self.group_max_size = Some(max_size.unwrap_or(u32::MAX));
+        self
+    }
+}
+
+pub fn check_and_create_token_22(params: CreateToken22Params<'_, '_>) -> ProgramResult {
+    let CreateToken22Params {
+        token_program_info,
+        authority_info,
+        asset_info,
+        owner_info,
+        payer_info,
+        system_program_info,
+        target_token_account_info,
+        spl_ata_program,
+        group_mint_opt_info,
+        name,
+        uri,
+        group_max_size,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("token_program", token_program_info, &spl_token_2022::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    let mut extensions = vec![ExtensionType::MetadataPointer];
+    if group_mint_opt_info.is_some() {
+        extensions.push(ExtensionType::GroupMemberPointer);
+    }
+    if group_max_size.is_some() {
+        extensions.push(ExtensionType::GroupPointer);
+    }
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions)?;
+
+    // the metadata and group (member) are appended by reallocating the
+    // mint, so it must hold the rent for them up front
+    let token_metadata = TokenMetadata {
+        mint: *asset_info.key,
+        name: name.clone(),
+        uri: uri.clone(),
+        ..Default::default()
+    };
+    if group_mint_opt_info.is_some() {
+        extensions.push(ExtensionType::TokenGroupMember);
+    }
+    if group_max_size.is_some() {
+        extensions.push(ExtensionType::TokenGroup);
+    }
+    let full_space = ExtensionType::try_calculate_account_len::<Mint>(&extensions)?
+        + token_metadata.tlv_size_of()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_info.key,
+            asset_info.key,
+            Rent::get()?.minimum_balance(full_space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        &[
+            payer_info.clone(),
+            asset_info.clone(),
+            system_program_info.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    invoke_signed(
+        &metadata_pointer::instruction::initialize(
+            &spl_token_2022::ID,
+            asset_info.key,
+            Some(*authority_info.key),
+            Some(*asset_info.key),
+        )?,
+        &[asset_info.clone(), token_program_info.clone()],
+        signer_seeds,
+    )?;
+
+    if group_mint_opt_info.is_some() {
+        invoke_signed(
+            &group_member_pointer::instruction::initialize(
+                &spl_token_2022::ID,
+                asset_info.key,
+                Some(*authority_info.key),
+                Some(*asset_info.key),
+            )?,
+            &[asset_info.clone(), token_program_info.clone()],
+            signer_seeds,
+        )?;
+    }
+
+    if group_max_size.is_some() {
+        invoke_signed(
+            &group_pointer::instruction::initialize(
+                &spl_token_2022::ID,
+                asset_info.key,
+                Some(*authority_info.key),
+                Some(*asset_info.key),
+            )?,
+            &[asset_info.clone(), token_program_info.clone()],
+            signer_seeds,
+        )?;
+    }
+
+    invoke_signed(
+        &initialize_mint2(
+            &spl_token_2022::ID,
+            asset_info.key,
+            authority_info.key,
+            None,
+            0,
+        )?,
+        &[asset_info.clone(), token_program_info.clone()],
+        signer_seeds,
+    )?;
+
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::initialize(
+            &spl_token_2022::ID,
+            asset_info.key,
+            authority_info.key,
+            asset_info.key,
+            authority_info.key,
+            name,
+            String::new(),
+            uri,
+        ),
+        &[
+            asset_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    if let Some(max_size) = group_max_size {
+        invoke_signed(
+            &spl_token_group_interface::instruction::initialize_group(
+                &spl_token_2022::ID,
+                asset_info.key,
+                asset_info.key,
+                authority_info.key,
+                Some(*authority_info.key),
+                max_size,
+            ),
+            &[
+                asset_info.clone(),
+                authority_info.clone(),
+                token_program_info.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+
+    if let Some(group_mint_info) = group_mint_opt_info {
+        // the authority must also be the update authority of the group
+        invoke_signed(
+            &spl_token_group_interface::instruction::initialize_member(
+                &spl_token_2022::ID,
+                asset_info.key,
+                asset_info.key,
+                authority_info.key,
+                group_mint_info.key,
+                authority_info.key,
+            ),
+            &[
+                asset_info.clone(),
+                authority_info.clone(),
+                group_mint_info.clone(),
+                token_program_info.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+
+    invoke_signed(
+        &spl_associated_token_account::instruction::create_associated_token_account(
+            payer_info.key,
+            owner_info.key,
+            asset_info.key,
+            &spl_token_2022::ID,
+        ),
+        &[
+            payer_info.clone(),
+            target_token_account_info.clone(),
+            owner_info.clone(),
+            asset_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            spl_ata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    invoke_signed(
+        &mint_to(
+            &spl_token_2022::ID,
+            asset_info.key,
+            target_token_account_info.key,
+            authority_info.key,
+            &[],
+            1,
+        )?,
+        &[
+            asset_info.clone(),
+            target_token_account_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    // supply is fixed at one
+    invoke_signed(
+        &set_authority(
+            &spl_token_2022::ID,
+            asset_info.key,
+            None,
+            AuthorityType::MintTokens,
+            authority_info.key,
+            &[],
+        )?,
+        &[
+            asset_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
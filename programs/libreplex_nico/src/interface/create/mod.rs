@@ -0,0 +1,136 @@
+pub mod create_core;
+pub mod create_metaplex;
+pub mod create_nifty;
+pub mod create_token_22;
+
+use create_core::{check_and_create_core, CreateCoreParams};
+use create_metaplex::{check_and_create_metaplex, CreateMetaplexParams};
+use create_nifty::{check_and_create_nifty, CreateNiftyParams};
+use create_token_22::{check_and_create_token_22, CreateToken22Params};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::{associated_token_address, find_account, metadata_address, Nico, NicoCreator};
+
+/// Standard of the asset created by [`Nico::create`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NicoStandard {
+    Nifty,
+    MxCore,
+    // token-2022 mint with the metadata (and group member) extensions
+    Token22Extension,
+    MxNonProgrammable,
+    Mxprogrammable,
+}
+
+pub struct NicoCreateParams<'a> {
+    pub standard: NicoStandard,
+    pub name: String,
+    pub uri: String,
+    // basis points. not supported on token-2022
+    pub royalties: u16,
+    // shares must add up to 100. not supported on token-2022
+    pub creators: Vec<NicoCreator>,
+    // group asset, core collection, collection mint or token-2022 group
+    // mint. the authority must be able to add members to it
+    pub group: Option<&'a AccountInfo<'a>>,
+    pub owner: &'a AccountInfo<'a>,
+}
+
+impl<'a, 'b> Nico<'a> {
+    /// Create a new asset of the chosen standard, owned by `params.owner`,
+    /// and return it as a Nico. `asset_info` is the new asset (or mint)
+    /// account and must sign, as must the authority (or the payer if no
+    /// authority is given), which becomes the update and mint authority.
+    pub fn create(
+        asset_info: &'a AccountInfo<'a>,
+        params: NicoCreateParams<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> Result<Nico<'a>, ProgramError> {
+        let authority = authority.unwrap_or(payer);
+
+        match params.standard {
+            NicoStandard::Nifty => {
+                let nifty_params = CreateNiftyParams::from_nico_create_params(
+                    asset_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_nifty(nifty_params)?;
+                Ok(Nico::try_from_accounts(asset_info, None, None, None)?)
+            }
+            NicoStandard::MxCore => {
+                let core_params = CreateCoreParams::from_nico_create_params(
+                    asset_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_core(core_params)?;
+                Ok(Nico::try_from_accounts(asset_info, None, None, None)?)
+            }
+            NicoStandard::Token22Extension => {
+                let token_22_params = CreateToken22Params::from_nico_create_params(
+                    asset_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_token_22(token_22_params)?;
+
+                let token_account_info = find_account(
+                    &associated_token_address(
+                        params.owner.key,
+                        asset_info.key,
+                        &spl_token_2022::ID,
+                    ),
+                    remaining_accounts,
+                    "target_ata",
+                )?;
+                Ok(Nico::try_from_accounts(
+                    asset_info,
+                    None,
+                    Some(params.owner),
+                    Some(token_account_info),
+                )?)
+            }
+            NicoStandard::MxNonProgrammable | NicoStandard::Mxprogrammable => {
+                let metaplex_params = CreateMetaplexParams::from_nico_create_params(
+                    asset_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_metaplex(metaplex_params)?;
+
+                let metadata_info = find_account(
+                    &metadata_address(asset_info.key),
+                    remaining_accounts,
+                    "metadata",
+                )?;
+                let token_account_info = find_account(
+                    &associated_token_address(params.owner.key, asset_info.key, &spl_token::ID),
+                    remaining_accounts,
+                    "target_ata",
+                )?;
+                Ok(Nico::try_from_accounts(
+                    asset_info,
+                    Some(metadata_info),
+                    Some(params.owner),
+                    Some(token_account_info),
+                )?)
+            }
+        }
+    }
+}
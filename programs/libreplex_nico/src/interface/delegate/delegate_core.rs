@@ -0,0 +1,150 @@
+use mpl_core::{
+    instructions::{
+        AddPluginV1Cpi, AddPluginV1InstructionArgs, ApprovePluginAuthorityV1Cpi,
+        ApprovePluginAuthorityV1InstructionArgs, RevokePluginAuthorityV1Cpi,
+        RevokePluginAuthorityV1InstructionArgs,
+    },
+    list_plugins,
+    types::{BurnDelegate, FreezeDelegate, Plugin, PluginAuthority, PluginType, TransferDelegate},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, Error, NicoDelegateParams, NicoDelegateRole};
+
+pub struct DelegateCoreParams<'a, 'b> {
+    pub mpl_core_program_info: &'a AccountInfo<'a>,
+    pub authority_info: Option<&'a AccountInfo<'a>>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub delegate_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub collection_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> DelegateCoreParams<'a, 'b> {
+    pub fn from_nico_delegate_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoDelegateParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<DelegateCoreParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params
+                .base
+                .accounts
+                .find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_core_program_info =
+            params
+                .base
+                .accounts
+                .find(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(DelegateCoreParams {
+            mpl_core_program_info,
+            authority_info: params.base.authority_info,
+            asset_info,
+            payer_info: params.base.payer_info,
+            delegate_info: params.delegate_info,
+            system_program_info,
+            collection_asset_opt_info: params.base.group_asset_opt_info,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_delegate_core(
+    params: DelegateCoreParams<'_, '_>,
+    role: NicoDelegateRole,
+    approve: bool,
+) -> ProgramResult {
+    let DelegateCoreParams {
+        mpl_core_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        delegate_info,
+        system_program_info,
+        collection_asset_opt_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("mpl_core_program", mpl_core_program_info, &mpl_core::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    // core delegates are owner managed plugins whose authority is the delegate
+    let plugins = match role {
+        NicoDelegateRole::Transfer => vec![Plugin::TransferDelegate(TransferDelegate {})],
+        NicoDelegateRole::Utility => vec![
+            Plugin::FreezeDelegate(FreezeDelegate { frozen: false }),
+            Plugin::BurnDelegate(BurnDelegate {}),
+        ],
+        NicoDelegateRole::Sale | NicoDelegateRole::LockedTransfer { .. } => {
+            msg!("Sale and locked transfer delegates are not supported on core assets");
+            return Err(Error::UnsupportedDelegateRole.into());
+        }
+    };
+
+    let existing_plugins = list_plugins(&asset_info.try_borrow_data()?).unwrap_or_default();
+
+    for plugin in plugins {
+        let plugin_type = PluginType::from(&plugin);
+        let exists = existing_plugins.contains(&plugin_type);
+
+        if approve && exists {
+            ApprovePluginAuthorityV1Cpi {
+                __program: mpl_core_program_info,
+                asset: asset_info,
+                collection: collection_asset_opt_info,
+                payer: payer_info,
+                authority: authority_info,
+                system_program: system_program_info,
+                log_wrapper: None,
+                __args: ApprovePluginAuthorityV1InstructionArgs {
+                    plugin_type,
+                    new_authority: PluginAuthority::Address {
+                        address: *delegate_info.key,
+                    },
+                },
+            }
+            .invoke_signed(signer_seeds)?;
+        } else if approve {
+            AddPluginV1Cpi {
+                __program: mpl_core_program_info,
+                asset: asset_info,
+                collection: collection_asset_opt_info,
+                payer: payer_info,
+                authority: authority_info,
+                system_program: system_program_info,
+                log_wrapper: None,
+                __args: AddPluginV1InstructionArgs {
+                    plugin,
+                    init_authority: Some(PluginAuthority::Address {
+                        address: *delegate_info.key,
+                    }),
+                },
+            }
+            .invoke_signed(signer_seeds)?;
+        } else if exists {
+            // authority goes back to the owner
+            RevokePluginAuthorityV1Cpi {
+                __program: mpl_core_program_info,
+                asset: asset_info,
+                collection: collection_asset_opt_info,
+                payer: payer_info,
+                authority: authority_info,
+                system_program: system_program_info,
+                log_wrapper: None,
+                __args: RevokePluginAuthorityV1InstructionArgs { plugin_type },
+            }
+            .invoke_signed(signer_seeds)?;
+        }
+        // revoking a delegate that was never added is a no-op
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke_signed,
+    program_error::ProgramError,
+};
+
+use crate::{Error, NicoDelegateParams, NicoDelegateRole};
+
+pub struct DelegateLegacyParams<'a, 'b> {
+    pub spl_token_program_info: &'a AccountInfo<'a>,
+    // owner of the token account
+    pub owner_info: &'a AccountInfo<'a>,
+    pub delegate_info: &'a AccountInfo<'a>,
+    token_account_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> DelegateLegacyParams<'a, 'b> {
+    pub fn from_nico_delegate_params(
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoDelegateParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<DelegateLegacyParams<'a, 'b>, ProgramError> {
+        let spl_token_program_info = params.base.accounts.find(
+            &params.base.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+
+        Ok(DelegateLegacyParams {
+            spl_token_program_info,
+            owner_info: params.base.authority_info.map_or(params.base.payer_info, |x| x),
+            delegate_info: params.delegate_info,
+            token_account_info: current_token_account,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_delegate_legacy(
+    params: DelegateLegacyParams<'_, '_>,
+    role: NicoDelegateRole,
+    approve: bool,
+) -> ProgramResult {
+    let DelegateLegacyParams {
+        spl_token_program_info,
+        owner_info,
+        delegate_info,
+        token_account_info,
+        signer_seeds,
+    } = params;
+
+    if spl_token_program_info.key != &spl_token::ID
+        && spl_token_program_info.key != &spl_token_2022::ID
+    {
+        return Err(Error::InvalidTokenProgram.into());
+    }
+
+    // a token account has a single delegate, so the roles all map onto it
+    if let NicoDelegateRole::Sale | NicoDelegateRole::LockedTransfer { .. } = role {
+        msg!("Sale and locked transfer delegates are only supported on programmable NFTs");
+        return Err(Error::UnsupportedDelegateRole.into());
+    }
+
+    if approve {
+        invoke_signed(
+            &spl_token_2022::instruction::approve(
+                spl_token_program_info.key,
+                token_account_info.key,
+                delegate_info.key,
+                owner_info.key,
+                &[],
+                1,
+            )?,
+            &[
+                token_account_info.clone(),
+                delegate_info.clone(),
+                owner_info.clone(),
+            ],
+            signer_seeds,
+        )?;
+    } else {
+        invoke_signed(
+            &spl_token_2022::instruction::revoke(
+                spl_token_program_info.key,
+                token_account_info.key,
+                owner_info.key,
+                &[],
+            )?,
+            &[token_account_info.clone(), owner_info.clone()],
+            signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
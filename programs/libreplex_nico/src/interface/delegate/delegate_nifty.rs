@@ -0,0 +1,87 @@
+use nifty_asset::{
+    instructions::{
+        ApproveCpi as NiftyApproveCpi, ApproveInstructionArgs, RevokeCpi as NiftyRevokeCpi,
+        RevokeInstructionArgs,
+    },
+    types::{DelegateInput, DelegateRole},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+};
+
+use crate::{assertions::assert_same_pubkeys, Error, NicoDelegateParams, NicoDelegateRole};
+
+pub struct DelegateNiftyParams<'a, 'b> {
+    pub nifty_program_info: &'a AccountInfo<'a>,
+    pub owner_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub delegate_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> DelegateNiftyParams<'a, 'b> {
+    pub fn from_nico_delegate_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoDelegateParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<DelegateNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info =
+            params.base.accounts.find(&nifty_asset::ID, remaining_accounts, "nifty_asset")?;
+        Ok(DelegateNiftyParams {
+            nifty_program_info,
+            owner_info: params.base.authority_info.map_or(params.base.payer_info, |x| x),
+            asset_info,
+            delegate_info: params.delegate_info,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_delegate_nifty(
+    params: DelegateNiftyParams<'_, '_>,
+    role: NicoDelegateRole,
+    approve: bool,
+) -> ProgramResult {
+    let DelegateNiftyParams {
+        nifty_program_info,
+        owner_info,
+        asset_info,
+        delegate_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("nifty_program", nifty_program_info, &nifty_asset::ID)?;
+
+    let roles = match role {
+        NicoDelegateRole::Transfer => vec![DelegateRole::Transfer],
+        NicoDelegateRole::Utility => vec![DelegateRole::Lock, DelegateRole::Burn],
+        NicoDelegateRole::Sale | NicoDelegateRole::LockedTransfer { .. } => {
+            msg!("Sale and locked transfer delegates are not supported on nifty assets");
+            return Err(Error::UnsupportedDelegateRole.into());
+        }
+    };
+
+    if approve {
+        NiftyApproveCpi {
+            __program: nifty_program_info,
+            asset: asset_info,
+            owner: owner_info,
+            delegate: delegate_info,
+            __args: ApproveInstructionArgs {
+                delegate_input: DelegateInput::Some { roles },
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+    } else {
+        NiftyRevokeCpi {
+            __program: nifty_program_info,
+            asset: asset_info,
+            signer: owner_info,
+            __args: RevokeInstructionArgs {
+                delegate_input: DelegateInput::Some { roles },
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_token_metadata::{
+    accounts::Metadata,
+    instructions::{DelegateCpi, DelegateInstructionArgs, RevokeCpi, RevokeInstructionArgs},
+    types::{DelegateArgs, ProgrammableConfig, RevokeArgs},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{
+    assertions::assert_same_pubkeys, edition_address, metadata_address, token_record_address,
+    Error, NicoDelegateParams, NicoDelegateRole, AUTH_RULES_PROGRAM_ID,
+};
+
+pub struct DelegatePnftParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    // owner of the token account
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub delegate_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    token_account_info: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    token_record_info: &'a AccountInfo<'a>,
+    sysvar_instruction_info: &'a AccountInfo<'a>,
+    spl_token_program_info: &'a AccountInfo<'a>,
+    auth_rules_program_info: &'a AccountInfo<'a>,
+    auth_rules_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> DelegatePnftParams<'a, 'b> {
+    pub fn from_nico_delegate_params(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoDelegateParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<DelegatePnftParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params.base.accounts.find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_token_program_info = params.base.accounts.find(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let spl_token_program_info = params.base.accounts.find(
+            &params.base.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+
+        let auth_rules_program_info = params.base.accounts.find(
+            &AUTH_RULES_PROGRAM_ID,
+            remaining_accounts,
+            "auth_rules_program",
+        )?;
+
+        let sysvar_instruction_info = params.base.accounts.find(
+            &sysvar_instructions_program::ID,
+            remaining_accounts,
+            "sysvar_instructions_program",
+        )?;
+
+        let metadata_info = params.base.accounts.find(
+            &metadata_address(&params.base.nico_pubkey),
+            remaining_accounts,
+            "metadata",
+        )?;
+
+        let metadata = Metadata::safe_deserialize(&metadata_info.try_borrow_data()?)
+            .map_err(|error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            })?;
+
+        let auth_rules_info = match metadata.programmable_config {
+            Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+            None => None,
+        }
+        .map(|x| params.base.accounts.find(&x, remaining_accounts, "auth_rule"))
+        .transpose()?;
+
+        let edition_info = params.base.accounts.find(
+            &edition_address(&params.base.nico_pubkey),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        let token_record_info = params.base.accounts.find(
+            &token_record_address(&params.base.nico_pubkey, current_token_account.key),
+            remaining_accounts,
+            "token_record",
+        )?;
+
+        Ok(DelegatePnftParams {
+            mpl_token_program_info,
+            authority_info: params.base.authority_info.map_or(params.base.payer_info, |x| x),
+            asset_info: nico_asset_info,
+            payer_info: params.base.payer_info,
+            delegate_info: params.delegate_info,
+            system_program_info,
+            token_account_info: current_token_account,
+            metadata: metadata_info,
+            edition: edition_info,
+            token_record_info,
+            sysvar_instruction_info,
+            spl_token_program_info,
+            auth_rules_program_info,
+            auth_rules_info,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_delegate_pnft(
+    params: DelegatePnftParams<'_, '_>,
+    role: NicoDelegateRole,
+    approve: bool,
+) -> ProgramResult {
+    let DelegatePnftParams {
+        mpl_token_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        delegate_info,
+        system_program_info,
+        token_account_info,
+        metadata,
+        edition,
+        token_record_info,
+        sysvar_instruction_info,
+        spl_token_program_info,
+        auth_rules_program_info,
+        auth_rules_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    if approve {
+        let delegate_args = match role {
+            NicoDelegateRole::Transfer => DelegateArgs::TransferV1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            NicoDelegateRole::Sale => DelegateArgs::SaleV1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            NicoDelegateRole::Utility => DelegateArgs::UtilityV1 {
+                amount: 1,
+                authorization_data: None,
+            },
+            NicoDelegateRole::LockedTransfer { locked_address } => {
+                DelegateArgs::LockedTransferV1 {
+                    amount: 1,
+                    locked_address,
+                    authorization_data: None,
+                }
+            }
+        };
+
+        DelegateCpi {
+            __program: mpl_token_program_info,
+            delegate_record: None,
+            delegate: delegate_info,
+            metadata,
+            master_edition: Some(edition),
+            token_record: Some(token_record_info),
+            mint: asset_info,
+            token: Some(token_account_info),
+            authority: authority_info,
+            payer: payer_info,
+            system_program: system_program_info,
+            sysvar_instructions: sysvar_instruction_info,
+            spl_token_program: Some(spl_token_program_info),
+            authorization_rules_program: Some(auth_rules_program_info),
+            authorization_rules: auth_rules_info,
+            __args: DelegateInstructionArgs { delegate_args },
+        }
+        .invoke_signed(signer_seeds)?;
+    } else {
+        let revoke_args = match role {
+            NicoDelegateRole::Transfer => RevokeArgs::TransferV1,
+            NicoDelegateRole::Sale => RevokeArgs::SaleV1,
+            NicoDelegateRole::Utility => RevokeArgs::UtilityV1,
+            NicoDelegateRole::LockedTransfer { .. } => RevokeArgs::LockedTransferV1,
+        };
+
+        RevokeCpi {
+            __program: mpl_token_program_info,
+            delegate_record: None,
+            delegate: delegate_info,
+            metadata,
+            master_edition: Some(edition),
+            token_record: Some(token_record_info),
+            mint: asset_info,
+            token: Some(token_account_info),
+            authority: authority_info,
+            payer: payer_info,
+            system_program: system_program_info,
+            sysvar_instructions: sysvar_instruction_info,
+            spl_token_program: Some(spl_token_program_info),
+            authorization_rules_program: Some(auth_rules_program_info),
+            authorization_rules: auth_rules_info,
+            __args: RevokeInstructionArgs { revoke_args },
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+
+    Ok(())
+}
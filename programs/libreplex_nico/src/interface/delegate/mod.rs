@@ -0,0 +1,269 @@
+pub mod delegate_core;
+pub mod delegate_legacy;
+pub mod delegate_nifty;
+pub mod delegate_pnft;
+
+use delegate_core::{check_and_delegate_core, DelegateCoreParams};
+use delegate_legacy::{check_and_delegate_legacy, DelegateLegacyParams};
+use delegate_nifty::{check_and_delegate_nifty, DelegateNiftyParams};
+use delegate_pnft::{check_and_delegate_pnft, DelegatePnftParams};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{Error, MetadataType, Nico, NicoParams, NicoType};
+
+/// Delegate roles that can be approved on any Nico. Each standard maps
+/// them onto its own delegate mechanism.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NicoDelegateRole {
+    // can transfer the asset
+    Transfer,
+    // can transfer the asset, which the owner cannot move while listed (pNFT only)
+    Sale,
+    // can lock and burn the asset
+    Utility,
+    // can transfer the asset to the locked address only (pNFT only)
+    LockedTransfer { locked_address: Pubkey },
+}
+
+pub struct NicoDelegateParams<'a, 'b> {
+    pub base: NicoParams<'a, 'b>,
+    pub delegate_info: &'a AccountInfo<'a>,
+}
+
+impl<'a: 'c, 'b, 'c> NicoDelegateParams<'a, 'b> {
+    pub fn new(
+        nico: &'c Nico<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        delegate_info: &'a AccountInfo<'a>,
+        authority_info: Option<&'a AccountInfo<'a>>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<NicoDelegateParams<'a, 'b>, ProgramError> {
+        Ok(NicoDelegateParams {
+            base: NicoParams::new(
+                nico,
+                payer_info,
+                authority_info,
+                signer_seeds,
+                remaining_accounts,
+            )?,
+            delegate_info,
+        })
+    }
+}
+
+impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Approve a delegate with the given role. The authority (or the payer
+    /// if no authority is given) must be the owner of the asset.
+    #[allow(clippy::too_many_arguments)]
+    pub fn approve_delegate(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        delegate: &'a AccountInfo<'a>,
+        role: NicoDelegateRole,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        self.set_delegate(
+            asset_info,
+            payer,
+            delegate,
+            role,
+            authority,
+            remaining_accounts,
+            signer_seeds,
+            true,
+        )
+    }
+
+    /// Revoke a delegate previously approved with [`Nico::approve_delegate`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn revoke_delegate(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        delegate: &'a AccountInfo<'a>,
+        role: NicoDelegateRole,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        self.set_delegate(
+            asset_info,
+            payer,
+            delegate,
+            role,
+            authority,
+            remaining_accounts,
+            signer_seeds,
+            false,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn set_delegate(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        delegate: &'a AccountInfo<'a>,
+        role: NicoDelegateRole,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        approve: bool,
+    ) -> ProgramResult {
+        let params = NicoDelegateParams::new(
+            self,
+            payer,
+            delegate,
+            authority,
+            signer_seeds,
+            remaining_accounts,
+        )?;
+
+        self.set_delegate_with_params(asset_info, params, role, remaining_accounts, approve)
+    }
+
+    /// Same as [`Nico::approve_delegate`] and [`Nico::revoke_delegate`],
+    /// with the params built by the caller, e.g. to set the accounts parsed
+    /// by [`crate::NicoAccountLayout::parse`].
+    pub fn set_delegate_with_params(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        params: NicoDelegateParams<'a, 'b>,
+        role: NicoDelegateRole,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        approve: bool,
+    ) -> ProgramResult {
+        match &self.nico_type {
+            NicoType::Nifty => {
+                let nifty_params = DelegateNiftyParams::from_nico_delegate_params(
+                    asset_info,
+                    &params,
+                    remaining_accounts,
+                )?;
+                check_and_delegate_nifty(nifty_params, role, approve)
+            }
+            NicoType::MxCore => {
+                let core_params = DelegateCoreParams::from_nico_delegate_params(
+                    asset_info,
+                    &params,
+                    remaining_accounts,
+                )?;
+                check_and_delegate_core(core_params, role, approve)
+            }
+            NicoType::Compressed { .. } => {
+                msg!("Delegating compressed Nicos is not supported");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint {
+                metadata,
+                current_token_account,
+                ..
+            } => {
+                let metadata = match metadata {
+                    // token standard not set on the metadata. work it out from the accounts
+                    MetadataType::Unknown => {
+                        MetadataType::infer(asset_info, *current_token_account, remaining_accounts)?
+                    }
+                    x => *x,
+                };
+                match metadata {
+                    MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+                    MetadataType::Token22Extension | MetadataType::MxNonProgrammable => {
+                        let legacy_params = DelegateLegacyParams::from_nico_delegate_params(
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_delegate_legacy(legacy_params, role, approve)
+                    }
+                    MetadataType::Mxprogrammable => {
+                        let pnft_params = DelegatePnftParams::from_nico_delegate_params(
+                            asset_info,
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_delegate_pnft(pnft_params, role, approve)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_token_metadata::types::TokenStandard;
+    use solana_program::instruction::Instruction;
+    use spl_token_2022::instruction::TokenInstruction;
+
+    use crate::testing::{
+        fake_metadata, fake_program, fake_token_account, fake_wallet, record_invokes,
+    };
+
+    use super::*;
+
+    // approves `role` on a legacy NFT, returning the result and the
+    // instructions invoked
+    fn approve_on_legacy_nft(role: NicoDelegateRole) -> (ProgramResult, Vec<Instruction>) {
+        let owner_info = fake_wallet();
+        let (mint_info, metadata_info) =
+            fake_metadata(&spl_token::ID, Some(TokenStandard::NonFungible), None);
+        let token_account_info =
+            fake_token_account(mint_info.key, owner_info.key, &spl_token::ID, false);
+        let nico = Nico::try_from_accounts(
+            mint_info,
+            Some(metadata_info),
+            Some(owner_info),
+            Some(token_account_info),
+        )
+        .unwrap();
+        let remaining_accounts = vec![fake_program(&spl_token::ID).clone()].leak();
+
+        record_invokes(|| {
+            nico.approve_delegate(
+                mint_info,
+                owner_info,
+                fake_wallet(),
+                role,
+                None,
+                remaining_accounts,
+                &[],
+            )
+        })
+    }
+
+    #[test]
+    fn approves_transfer_delegate_on_legacy_nft() {
+        let (result, invokes) = approve_on_legacy_nft(NicoDelegateRole::Transfer);
+
+        result.unwrap();
+        assert_eq!(invokes[0].program_id, spl_token::ID);
+        assert!(matches!(
+            TokenInstruction::unpack(&invokes[0].data),
+            Ok(TokenInstruction::Approve { amount: 1 })
+        ));
+    }
+
+    #[test]
+    fn rejects_sale_delegates_on_legacy_nft() {
+        for role in [
+            NicoDelegateRole::Sale,
+            NicoDelegateRole::LockedTransfer {
+                locked_address: Pubkey::new_unique(),
+            },
+        ] {
+            let (result, invokes) = approve_on_legacy_nft(role);
+
+            assert_eq!(result, Err(Error::UnsupportedDelegateRole.into()));
+            assert!(invokes.is_empty());
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use mpl_token_metadata::accounts::{MasterEdition, Metadata, TokenRecord};
+use solana_program::{msg, program_error::ProgramError, pubkey, pubkey::Pubkey};
+
+use crate::Error;
+
+/// [`crate::transfer_pnft::AUTH_RULES`] as a pubkey.
+pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
+
+// Addresses of the accounts Nico looks up in the remaining accounts. The
+// program and the client both derive them here so that they cannot drift.
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Metadata::find_pda(mint).0
+}
+
+pub fn edition_address(mint: &Pubkey) -> Pubkey {
+    MasterEdition::find_pda(mint).0
+}
+
+pub fn token_record_address(mint: &Pubkey, token_account: &Pubkey) -> Pubkey {
+    TokenRecord::find_pda(mint, token_account).0
+}
+
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    associated_token_address_and_bump(wallet, mint, token_program).0
+}
+
+pub fn associated_token_address_and_bump(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::ID,
+    )
+}
+
+// The same addresses from a bump the client already knows. A single
+// create_program_address instead of the find_program_address loop.
+
+fn invalid_bump(name: &str, bump: u8) -> ProgramError {
+    msg!("Bump {} does not give a valid {} address", bump, name);
+    Error::InvalidBump.into()
+}
+
+pub fn metadata_address_with_bump(mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    Metadata::create_pda(*mint, bump).map_err(|_| invalid_bump("metadata", bump))
+}
+
+pub fn edition_address_with_bump(mint: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    MasterEdition::create_pda(*mint, bump).map_err(|_| invalid_bump("edition", bump))
+}
+
+pub fn token_record_address_with_bump(
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    TokenRecord::create_pda(*mint, *token_account, bump)
+        .map_err(|_| invalid_bump("token_record", bump))
+}
+
+pub fn associated_token_address_with_bump(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(
+        &[
+            wallet.as_ref(),
+            token_program.as_ref(),
+            mint.as_ref(),
+            &[bump],
+        ],
+        &spl_associated_token_account::ID,
+    )
+    .map_err(|_| invalid_bump("associated_token_account", bump))
+}
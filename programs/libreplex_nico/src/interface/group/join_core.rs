@@ -0,0 +1,80 @@
+use mpl_core::{
+    instructions::{UpdateV1Cpi, UpdateV1InstructionArgs},
+    types::UpdateAuthority,
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, find_account, NicoJoinGroupParams};
+
+pub struct JoinCoreParams<'a, 'b> {
+    pub mpl_core_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub collection_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> JoinCoreParams<'a, 'b> {
+    pub fn from_nico_join_group_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoJoinGroupParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<JoinCoreParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_core_program_info =
+            find_account(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(JoinCoreParams {
+            mpl_core_program_info,
+            authority_info: params.authority_info,
+            asset_info,
+            payer_info: params.payer_info,
+            system_program_info,
+            collection_info: params.group_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_join_core(params: JoinCoreParams<'_, '_>) -> ProgramResult {
+    let JoinCoreParams {
+        mpl_core_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        system_program_info,
+        collection_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("mpl_core_program", mpl_core_program_info, &mpl_core::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    // core assets are in a collection when the collection is their update
+    // authority, so the authority must manage both
+    UpdateV1Cpi {
+        __program: mpl_core_program_info,
+        asset: asset_info,
+        collection: Some(collection_info),
+        payer: payer_info,
+        authority: Some(authority_info),
+        system_program: system_program_info,
+        log_wrapper: None,
+        __args: UpdateV1InstructionArgs {
+            new_name: None,
+            new_uri: None,
+            new_update_authority: Some(UpdateAuthority::Collection(*collection_info.key)),
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
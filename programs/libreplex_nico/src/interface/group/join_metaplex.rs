@@ -0,0 +1,164 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_token_metadata::{
+    instructions::{UpdateV1Cpi, UpdateV1InstructionArgs, VerifyCollectionV1Cpi},
+    types::{Collection, CollectionDetailsToggle, CollectionToggle, RuleSetToggle, UsesToggle},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{
+    assertions::assert_same_pubkeys, edition_address, find_account, metadata_address,
+    NicoJoinGroupParams,
+};
+
+pub struct JoinMetaplexParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    sysvar_instruction_info: &'a AccountInfo<'a>,
+    collection_info: &'a AccountInfo<'a>,
+    collection_metadata_info: &'a AccountInfo<'a>,
+    collection_edition_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> JoinMetaplexParams<'a, 'b> {
+    pub fn from_nico_join_group_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoJoinGroupParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<JoinMetaplexParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            find_account(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_token_program_info = find_account(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let sysvar_instruction_info = find_account(
+            &sysvar_instructions_program::ID,
+            remaining_accounts,
+            "sysvar_instructions_program",
+        )?;
+
+        let metadata = find_account(
+            &metadata_address(asset_info.key),
+            remaining_accounts,
+            "metadata",
+        )?;
+
+        let edition = find_account(
+            &edition_address(asset_info.key),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        let collection_metadata_info = find_account(
+            &metadata_address(params.group_info.key),
+            remaining_accounts,
+            "collection_metadata",
+        )?;
+
+        let collection_edition_info = find_account(
+            &edition_address(params.group_info.key),
+            remaining_accounts,
+            "collection_master_edition",
+        )?;
+
+        Ok(JoinMetaplexParams {
+            mpl_token_program_info,
+            authority_info: params.authority_info,
+            asset_info,
+            payer_info: params.payer_info,
+            system_program_info,
+            metadata,
+            edition,
+            sysvar_instruction_info,
+            collection_info: params.group_info,
+            collection_metadata_info,
+            collection_edition_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_join_metaplex(params: JoinMetaplexParams<'_, '_>) -> ProgramResult {
+    let JoinMetaplexParams {
+        mpl_token_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        system_program_info,
+        metadata,
+        edition,
+        sysvar_instruction_info,
+        collection_info,
+        collection_metadata_info,
+        collection_edition_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    // the collection is set unverified by the update authority of the asset
+    // and then verified by the update authority of the collection, so the
+    // authority must be both
+    UpdateV1Cpi {
+        __program: mpl_token_program_info,
+        authority: authority_info,
+        delegate_record: None,
+        token: None,
+        mint: asset_info,
+        metadata,
+        edition: Some(edition),
+        payer: payer_info,
+        system_program: system_program_info,
+        sysvar_instructions: sysvar_instruction_info,
+        authorization_rules_program: None,
+        authorization_rules: None,
+        __args: UpdateV1InstructionArgs {
+            new_update_authority: None,
+            data: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+            collection: CollectionToggle::Set(Collection {
+                verified: false,
+                key: *collection_info.key,
+            }),
+            collection_details: CollectionDetailsToggle::None,
+            uses: UsesToggle::None,
+            rule_set: RuleSetToggle::None,
+            authorization_data: None,
+        },
+    }
+    .invoke_signed(signer_seeds)?;
+
+    VerifyCollectionV1Cpi {
+        __program: mpl_token_program_info,
+        authority: authority_info,
+        delegate_record: None,
+        metadata,
+        collection_mint: collection_info,
+        collection_metadata: Some(collection_metadata_info),
+        collection_master_edition: Some(collection_edition_info),
+        system_program: system_program_info,
+        sysvar_instructions: sysvar_instruction_info,
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use nifty_asset::instructions::GroupCpi;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+
+use crate::{assertions::assert_same_pubkeys, find_account, NicoJoinGroupParams};
+
+pub struct JoinNiftyParams<'a, 'b> {
+    pub nifty_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub group_asset_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> JoinNiftyParams<'a, 'b> {
+    pub fn from_nico_join_group_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoJoinGroupParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<JoinNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info = find_account(&nifty_asset::ID, remaining_accounts, "nifty_asset")?;
+
+        Ok(JoinNiftyParams {
+            nifty_program_info,
+            authority_info: params.authority_info,
+            asset_info,
+            group_asset_info: params.group_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_join_nifty(params: JoinNiftyParams<'_, '_>) -> ProgramResult {
+    let JoinNiftyParams {
+        nifty_program_info,
+        authority_info,
+        asset_info,
+        group_asset_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("nifty_program", nifty_program_info, &nifty_asset::ID)?;
+
+    // the authority must be the authority of both the asset and the group
+    GroupCpi {
+        __program: nifty_program_info,
+        asset: asset_info,
+        group: group_asset_info,
+        authority: authority_info,
+    }
+    .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
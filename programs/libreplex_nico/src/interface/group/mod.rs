@@ -0,0 +1,333 @@
+pub mod join_core;
+pub mod join_metaplex;
+pub mod join_nifty;
+
+use join_core::{check_and_join_core, JoinCoreParams};
+use join_metaplex::{check_and_join_metaplex, JoinMetaplexParams};
+use join_nifty::{check_and_join_nifty, JoinNiftyParams};
+use mpl_core::{accounts::BaseCollectionV1, types::Key};
+use mpl_token_metadata::accounts::Metadata;
+use nifty_asset::{extensions::ExtensionType, state::Asset};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use spl_token_group_interface::state::TokenGroup;
+
+use crate::{
+    assert_program_owner, assert_same_pubkeys,
+    create_core::{check_and_create_core, CreateCoreParams},
+    create_metaplex::{check_and_create_metaplex, CreateMetaplexParams},
+    create_nifty::{check_and_create_nifty, CreateNiftyParams},
+    create_token_22::{check_and_create_token_22, CreateToken22Params},
+    find_account, metadata_address, Error, MetadataType, Nico, NicoCreateParams, NicoStandard,
+    NicoType,
+};
+
+/// A group created by [`NicoGroup::create`]: a nifty group asset, a core
+/// collection, a token-2022 group mint or a sized collection mint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NicoGroup {
+    pub standard: NicoStandard,
+    pub pubkey: Pubkey,
+}
+
+impl NicoGroup {
+    /// Create a new group of the chosen standard. `params.group` must be
+    /// empty, groups cannot be nested. `max_size` caps the members of nifty
+    /// and token-2022 groups and is ignored by core and token metadata
+    /// collections. As with [`Nico::create`], the authority (or the payer if
+    /// no authority is given) becomes the update authority of the group, and
+    /// for mint based standards the collection token goes to `params.owner`.
+    pub fn create<'a, 'b>(
+        group_info: &'a AccountInfo<'a>,
+        params: NicoCreateParams<'a>,
+        max_size: Option<u32>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> Result<NicoGroup, ProgramError> {
+        if let Some(parent_info) = params.group {
+            msg!(
+                "Group {} cannot join group {}",
+                group_info.key,
+                parent_info.key
+            );
+            return Err(Error::InvalidGroup.into());
+        }
+
+        let authority = authority.unwrap_or(payer);
+
+        match params.standard {
+            NicoStandard::Nifty => {
+                let nifty_params = CreateNiftyParams::from_nico_create_params(
+                    group_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_nifty(nifty_params.as_group(max_size))?;
+            }
+            NicoStandard::MxCore => {
+                let core_params = CreateCoreParams::from_nico_create_params(
+                    group_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_core(core_params.as_group())?;
+            }
+            NicoStandard::Token22Extension => {
+                let token_22_params = CreateToken22Params::from_nico_create_params(
+                    group_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_token_22(token_22_params.as_group(max_size))?;
+            }
+            NicoStandard::MxNonProgrammable | NicoStandard::Mxprogrammable => {
+                let metaplex_params = CreateMetaplexParams::from_nico_create_params(
+                    group_info,
+                    &params,
+                    payer,
+                    authority,
+                    signer_seeds,
+                    remaining_accounts,
+                )?;
+                check_and_create_metaplex(metaplex_params.as_group())?;
+            }
+        }
+
+        Ok(NicoGroup {
+            standard: params.standard,
+            pubkey: *group_info.key,
+        })
+    }
+}
+
+pub struct NicoJoinGroupParams<'a, 'b> {
+    pub authority_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub group_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> NicoJoinGroupParams<'a, 'b> {
+    pub fn new(
+        group_info: &'a AccountInfo<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: Option<&'a AccountInfo<'a>>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> NicoJoinGroupParams<'a, 'b> {
+        NicoJoinGroupParams {
+            authority_info: authority_info.unwrap_or(payer_info),
+            payer_info,
+            group_info,
+            signer_seeds,
+        }
+    }
+}
+
+impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Add the asset to `group_info`, a group of the same standard. The
+    /// authority (or the payer if no authority is given) must be the update
+    /// authority of both the asset and the group. Classifying the asset again
+    /// afterwards fills in [`Nico::group`]. Token-2022 mints can only join a
+    /// group when created, see [`Nico::create`].
+    pub fn join_group(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        group_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        if let Some(group) = &self.group {
+            msg!("Nico {} is already in group {}", self.pubkey, group);
+            return Err(Error::InvalidGroup.into());
+        }
+        assert_same_pubkeys("asset", asset_info, &self.pubkey)?;
+
+        let params = NicoJoinGroupParams::new(group_info, payer, authority, signer_seeds);
+
+        match &self.nico_type {
+            NicoType::Nifty => {
+                let nifty_params = JoinNiftyParams::from_nico_join_group_params(
+                    asset_info,
+                    &params,
+                    remaining_accounts,
+                )?;
+                check_and_join_nifty(nifty_params)
+            }
+            NicoType::MxCore => {
+                let core_params = JoinCoreParams::from_nico_join_group_params(
+                    asset_info,
+                    &params,
+                    remaining_accounts,
+                )?;
+                check_and_join_core(core_params)
+            }
+            NicoType::Compressed { .. } => {
+                msg!("Compressed Nicos cannot join a group");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint {
+                metadata,
+                current_token_account,
+                ..
+            } => {
+                let metadata = match metadata {
+                    // token standard not set on the metadata. work it out from the accounts
+                    MetadataType::Unknown => {
+                        MetadataType::infer(asset_info, *current_token_account, remaining_accounts)?
+                    }
+                    x => *x,
+                };
+                match metadata {
+                    MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+                    MetadataType::Token22Extension => {
+                        // the member pointer and member are only written when
+                        // the mint is created, with its mint authority
+                        msg!("Token-2022 mints can only join a group when created");
+                        Err(Error::UnsupportedAssetType.into())
+                    }
+                    MetadataType::MxNonProgrammable | MetadataType::Mxprogrammable => {
+                        let metaplex_params = JoinMetaplexParams::from_nico_join_group_params(
+                            asset_info,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_join_metaplex(metaplex_params)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check that the asset belongs to `expected_group` and that the group
+    /// account, found in the remaining accounts, is a genuine collection of
+    /// the same standard as the asset.
+    pub fn assert_in_group(
+        &'c self,
+        expected_group: &Pubkey,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> ProgramResult {
+        if self.group.as_ref() != Some(expected_group) {
+            msg!(
+                "Nico {} has group {:?}, expected {}",
+                self.pubkey,
+                self.group,
+                expected_group
+            );
+            return Err(Error::NotInGroup.into());
+        }
+
+        let group_info = find_account(expected_group, remaining_accounts, "group")?;
+
+        match &self.nico_type {
+            NicoType::Nifty => assert_nifty_group(group_info),
+            NicoType::MxCore => assert_core_group(group_info),
+            NicoType::Compressed { .. } => {
+                msg!("Group membership of compressed Nicos cannot be checked");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint { metadata, .. } => match metadata {
+                MetadataType::Token22Extension => assert_token_22_group(group_info),
+                MetadataType::Unknown
+                | MetadataType::MxNonProgrammable
+                | MetadataType::Mxprogrammable => {
+                    assert_metaplex_group(group_info, remaining_accounts)
+                }
+            },
+        }
+    }
+}
+
+fn assert_nifty_group(group_info: &AccountInfo) -> ProgramResult {
+    assert_program_owner("group", group_info, &nifty_asset::ID)?;
+
+    let data = group_info.try_borrow_data()?;
+    if data.len() < Asset::LEN || !Asset::contains(ExtensionType::Grouping, &data) {
+        msg!("Nifty asset {} has no grouping extension", group_info.key);
+        return Err(Error::InvalidGroup.into());
+    }
+    Ok(())
+}
+
+fn assert_core_group(group_info: &AccountInfo) -> ProgramResult {
+    assert_program_owner("group", group_info, &mpl_core::ID)?;
+
+    let is_collection = BaseCollectionV1::from_bytes(&group_info.try_borrow_data()?)
+        .is_ok_and(|collection| collection.key == Key::CollectionV1);
+    if !is_collection {
+        msg!("Core account {} is not a collection", group_info.key);
+        return Err(Error::InvalidGroup.into());
+    }
+    Ok(())
+}
+
+fn assert_metaplex_group<'a>(
+    group_info: &'a AccountInfo<'a>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    if group_info.owner != &spl_token::ID && group_info.owner != &spl_token_2022::ID {
+        msg!("Collection mint {} is not owned by a token program", group_info.key);
+        return Err(Error::InvalidProgramOwner.into());
+    }
+
+    let collection_metadata_info = find_account(
+        &metadata_address(group_info.key),
+        remaining_accounts,
+        "collection_metadata",
+    )?;
+    assert_program_owner(
+        "collection_metadata",
+        collection_metadata_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    let collection_metadata =
+        Metadata::safe_deserialize(&collection_metadata_info.try_borrow_data()?).map_err(
+            |error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            },
+        )?;
+
+    // only sized collections carry collection details
+    if collection_metadata.collection_details.is_none() {
+        msg!("Collection {} is not a sized collection", group_info.key);
+        return Err(Error::InvalidGroup.into());
+    }
+    Ok(())
+}
+
+fn assert_token_22_group(group_info: &AccountInfo) -> ProgramResult {
+    assert_program_owner("group", group_info, &spl_token_2022::ID)?;
+
+    let data = group_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+
+    // guard against a group extension copied from another mint
+    let is_group = mint
+        .get_extension::<TokenGroup>()
+        .is_ok_and(|group| group.mint.eq(group_info.key));
+    if !is_group {
+        msg!("Mint {} has no token group extension", group_info.key);
+        return Err(Error::InvalidGroup.into());
+    }
+    Ok(())
+}
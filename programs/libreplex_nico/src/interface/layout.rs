@@ -0,0 +1,734 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    account_info::AccountInfo, instruction::AccountMeta, msg, program_error::ProgramError,
+    pubkey::Pubkey, system_program,
+};
+
+use crate::{
+    assert_same_pubkeys, assert_writable, associated_token_address, edition_address, find_account,
+    metadata_address, token_record_address, Error, MetadataType, Nico, NicoStandard, NicoType,
+    AUTH_RULES_PROGRAM_ID,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NicoAccountSpec {
+    pub name: &'static str,
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NicoAccountLookup {
+    // accounts in exactly the layout order, nothing left over
+    Positional,
+    // accounts in any order, extra accounts allowed
+    Search,
+}
+
+/// The accounts [`NicoAccountLayout::parse`] matched, in layout order. Set
+/// on the params of an operation, its accounts are taken from here first
+/// and only searched for in the remaining accounts if the layout did not
+/// cover them.
+#[derive(Clone, Default)]
+pub struct NicoAccounts<'a> {
+    accounts: Vec<(&'static str, &'a AccountInfo<'a>)>,
+}
+
+impl<'a> NicoAccounts<'a> {
+    /// The first account the layout lists under `name`.
+    pub fn get(&self, name: &str) -> Option<&'a AccountInfo<'a>> {
+        self.accounts
+            .iter()
+            .find(|(x, _)| x.eq(&name))
+            .map(|(_, x)| *x)
+    }
+
+    /// Same as [`find_account`], but checks the parsed accounts first.
+    pub fn find(
+        &self,
+        account_id: &Pubkey,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        name: &str,
+    ) -> Result<&'a AccountInfo<'a>, ProgramError> {
+        match self.accounts.iter().find(|(_, x)| x.key.eq(account_id)) {
+            Some((_, x)) => Ok(x),
+            None => find_account(account_id, remaining_accounts, name),
+        }
+    }
+}
+
+/// The remaining accounts a Nico operation expects, in order, with their
+/// writable requirements. Clients build the account list from it and the
+/// program checks the accounts it was given against it. None of them sign:
+/// the payer, the authority and new assets are passed to the operation
+/// directly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NicoAccountLayout {
+    pub accounts: Vec<NicoAccountSpec>,
+}
+
+impl NicoAccountLayout {
+    // an account requested twice is listed once, under its first name,
+    // writable if either request was
+    fn push(&mut self, name: &'static str, pubkey: Pubkey, is_writable: bool) {
+        match self.accounts.iter_mut().find(|x| x.pubkey == pubkey) {
+            Some(x) => x.is_writable |= is_writable,
+            None => self.accounts.push(NicoAccountSpec {
+                name,
+                pubkey,
+                is_writable,
+            }),
+        }
+    }
+
+    fn push_group(&mut self, nico: &Nico, is_writable: bool) {
+        if let Some(group) = nico.group {
+            self.push("group", group, is_writable);
+        }
+    }
+
+    // accounts shared by pNFT lock, unlock, approve and revoke
+    fn push_pnft_accounts(
+        &mut self,
+        nico: &Nico,
+        rule_set: Option<Pubkey>,
+    ) -> Result<(), ProgramError> {
+        self.push("system_program", system_program::ID, false);
+        self.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+        self.push("token_program", nico.owner_program, false);
+        self.push("auth_rules_program", AUTH_RULES_PROGRAM_ID, false);
+        self.push(
+            "sysvar_instructions_program",
+            sysvar_instructions_program::ID,
+            false,
+        );
+        self.push("metadata", metadata_address(&nico.pubkey), true);
+        if let Some(rule_set) = rule_set {
+            self.push("auth_rule", rule_set, false);
+        }
+        self.push("master_edition", edition_address(&nico.pubkey), false);
+        self.push(
+            "token_record",
+            token_record_address(&nico.pubkey, &current_token_account(nico)?),
+            true,
+        );
+        Ok(())
+    }
+
+    /// Layout for [`Nico::transfer`]. `rule_set` is the token auth rule set
+    /// of a pNFT. For compressed Nicos the tree config, merkle tree and
+    /// proof come last, so the proof can be sliced off the end.
+    pub fn transfer(
+        nico: &Nico,
+        recipient: &Pubkey,
+        authority: &Pubkey,
+        rule_set: Option<Pubkey>,
+    ) -> Result<NicoAccountLayout, ProgramError> {
+        let mut layout = NicoAccountLayout::default();
+        layout.push_group(nico, false);
+
+        match &nico.nico_type {
+            NicoType::Nifty => layout.push("nifty_asset", nifty_asset::ID, false),
+            NicoType::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoType::Compressed {
+                merkle_tree,
+                tree_config,
+                proof,
+                leaf,
+            } => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("bubblegum_program", mpl_bubblegum::ID, false);
+                layout.push("log_wrapper", SPL_NOOP_ID, false);
+                layout.push("compression_program", SPL_ACCOUNT_COMPRESSION_ID, false);
+                if leaf.owner != *authority {
+                    layout.push("leaf_owner", leaf.owner, false);
+                }
+                if leaf.delegate != *authority && leaf.delegate != leaf.owner {
+                    layout.push("leaf_delegate", leaf.delegate, false);
+                }
+                layout.push("tree_config", *tree_config.key, false);
+                layout.push("merkle_tree", *merkle_tree.key, true);
+                for node in proof.iter() {
+                    layout.push("proof", *node.key, false);
+                }
+            }
+            NicoType::Mint { metadata, .. } => match metadata_type(metadata)? {
+                MetadataType::Mxprogrammable => {
+                    let target_token_account =
+                        associated_token_address(recipient, &nico.pubkey, &nico.owner_program);
+                    layout.push("system_program", system_program::ID, false);
+                    layout.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+                    layout.push("token_program", nico.owner_program, false);
+                    layout.push(
+                        "associated_token_program",
+                        spl_associated_token_account::ID,
+                        false,
+                    );
+                    layout.push("auth_rules_program", AUTH_RULES_PROGRAM_ID, false);
+                    layout.push("metadata", metadata_address(&nico.pubkey), true);
+                    if let Some(rule_set) = rule_set {
+                        layout.push("auth_rule", rule_set, false);
+                    }
+                    layout.push(
+                        "sysvar_instructions_program",
+                        sysvar_instructions_program::ID,
+                        false,
+                    );
+                    layout.push("target_ata", target_token_account, true);
+                    layout.push(
+                        "source_token_record",
+                        token_record_address(&nico.pubkey, &current_token_account(nico)?),
+                        true,
+                    );
+                    layout.push(
+                        "target_token_record",
+                        token_record_address(&nico.pubkey, &target_token_account),
+                        true,
+                    );
+                    layout.push("master_edition", edition_address(&nico.pubkey), false);
+                }
+                _ => {
+                    layout.push("system_program", system_program::ID, false);
+                    layout.push("token_program", nico.owner_program, false);
+                    layout.push(
+                        "associated_token_program",
+                        spl_associated_token_account::ID,
+                        false,
+                    );
+                    layout.push(
+                        "target_ata",
+                        associated_token_address(recipient, &nico.pubkey, &nico.owner_program),
+                        true,
+                    );
+                }
+            },
+        }
+
+        Ok(layout)
+    }
+
+    /// Layout for [`Nico::burn`].
+    pub fn burn(nico: &Nico) -> Result<NicoAccountLayout, ProgramError> {
+        let mut layout = NicoAccountLayout::default();
+        layout.push_group(nico, true);
+
+        match &nico.nico_type {
+            NicoType::Nifty => layout.push("nifty_asset", nifty_asset::ID, false),
+            NicoType::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoType::Compressed { .. } => return Err(Error::UnsupportedAssetType.into()),
+            NicoType::Mint { metadata, .. } => {
+                let metadata = metadata_type(metadata)?;
+                if metadata == MetadataType::Token22Extension {
+                    return Err(Error::UnsupportedAssetType.into());
+                }
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+                layout.push("token_program", nico.owner_program, false);
+                layout.push(
+                    "sysvar_instructions_program",
+                    sysvar_instructions_program::ID,
+                    false,
+                );
+                layout.push("metadata", metadata_address(&nico.pubkey), true);
+                layout.push("master_edition", edition_address(&nico.pubkey), true);
+                if metadata == MetadataType::Mxprogrammable {
+                    layout.push(
+                        "token_record",
+                        token_record_address(&nico.pubkey, &current_token_account(nico)?),
+                        true,
+                    );
+                }
+                if let Some(group) = nico.group {
+                    layout.push("collection_metadata", metadata_address(&group), true);
+                }
+            }
+        }
+
+        Ok(layout)
+    }
+
+    /// Layout for [`Nico::lock`] and [`Nico::unlock`].
+    pub fn lock(nico: &Nico, rule_set: Option<Pubkey>) -> Result<NicoAccountLayout, ProgramError> {
+        let mut layout = NicoAccountLayout::default();
+        layout.push_group(nico, true);
+
+        match &nico.nico_type {
+            NicoType::Nifty => layout.push("nifty_asset", nifty_asset::ID, false),
+            NicoType::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoType::Compressed { .. } => return Err(Error::UnsupportedAssetType.into()),
+            NicoType::Mint { metadata, .. } => match metadata_type(metadata)? {
+                MetadataType::Mxprogrammable => layout.push_pnft_accounts(nico, rule_set)?,
+                MetadataType::MxNonProgrammable => {
+                    layout.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+                    layout.push("token_program", nico.owner_program, false);
+                    layout.push("master_edition", edition_address(&nico.pubkey), false);
+                }
+                _ => return Err(Error::UnsupportedAssetType.into()),
+            },
+        }
+
+        Ok(layout)
+    }
+
+    /// Layout for [`Nico::approve_delegate`] and [`Nico::revoke_delegate`].
+    pub fn delegate(
+        nico: &Nico,
+        rule_set: Option<Pubkey>,
+    ) -> Result<NicoAccountLayout, ProgramError> {
+        let mut layout = NicoAccountLayout::default();
+        layout.push_group(nico, true);
+
+        match &nico.nico_type {
+            NicoType::Nifty => layout.push("nifty_asset", nifty_asset::ID, false),
+            NicoType::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoType::Compressed { .. } => return Err(Error::UnsupportedAssetType.into()),
+            NicoType::Mint { metadata, .. } => match metadata_type(metadata)? {
+                MetadataType::Mxprogrammable => layout.push_pnft_accounts(nico, rule_set)?,
+                _ => layout.push("token_program", nico.owner_program, false),
+            },
+        }
+
+        Ok(layout)
+    }
+
+    /// Layout for [`Nico::create`]. The group is the account given as
+    /// `NicoCreateParams::group`.
+    pub fn create(
+        standard: NicoStandard,
+        asset: &Pubkey,
+        owner: &Pubkey,
+        group: Option<Pubkey>,
+    ) -> NicoAccountLayout {
+        let mut layout = NicoAccountLayout::default();
+
+        match standard {
+            NicoStandard::Nifty => {
+                layout.push("nifty_asset", nifty_asset::ID, false);
+                layout.push("system_program", system_program::ID, false);
+            }
+            NicoStandard::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoStandard::Token22Extension => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("token_program", spl_token_2022::ID, false);
+                layout.push(
+                    "associated_token_program",
+                    spl_associated_token_account::ID,
+                    false,
+                );
+                layout.push(
+                    "target_ata",
+                    associated_token_address(owner, asset, &spl_token_2022::ID),
+                    true,
+                );
+            }
+            NicoStandard::MxNonProgrammable | NicoStandard::Mxprogrammable => {
+                let target_token_account = associated_token_address(owner, asset, &spl_token::ID);
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+                layout.push("token_program", spl_token::ID, false);
+                layout.push(
+                    "associated_token_program",
+                    spl_associated_token_account::ID,
+                    false,
+                );
+                layout.push(
+                    "sysvar_instructions_program",
+                    sysvar_instructions_program::ID,
+                    false,
+                );
+                layout.push("metadata", metadata_address(asset), true);
+                layout.push("master_edition", edition_address(asset), true);
+                layout.push("target_ata", target_token_account, true);
+                if standard == NicoStandard::Mxprogrammable {
+                    layout.push(
+                        "token_record",
+                        token_record_address(asset, &target_token_account),
+                        true,
+                    );
+                }
+                if let Some(group) = group {
+                    layout.push("group", group, false);
+                    layout.push("collection_metadata", metadata_address(&group), true);
+                    layout.push("collection_master_edition", edition_address(&group), false);
+                }
+            }
+        }
+
+        if let Some(group) = group {
+            layout.push("group", group, true);
+        }
+
+        layout
+    }
+
+    /// Layout for [`Nico::join_group`]. [`crate::NicoGroup::create`] takes
+    /// the same accounts as [`NicoAccountLayout::create`], without a group.
+    pub fn join_group(nico: &Nico, group: &Pubkey) -> Result<NicoAccountLayout, ProgramError> {
+        let mut layout = NicoAccountLayout::default();
+
+        match &nico.nico_type {
+            NicoType::Nifty => layout.push("nifty_asset", nifty_asset::ID, false),
+            NicoType::MxCore => {
+                layout.push("system_program", system_program::ID, false);
+                layout.push("mpl_core_program", mpl_core::ID, false);
+            }
+            NicoType::Compressed { .. } => return Err(Error::UnsupportedAssetType.into()),
+            // the accounts are the same for both Metaplex standards, and hold
+            // the edition the join infers an unknown one from
+            NicoType::Mint { metadata, .. } => match metadata {
+                MetadataType::Token22Extension => return Err(Error::UnsupportedAssetType.into()),
+                _ => {
+                    layout.push("system_program", system_program::ID, false);
+                    layout.push("mpl_token_metadata", mpl_token_metadata::ID, false);
+                    layout.push(
+                        "sysvar_instructions_program",
+                        sysvar_instructions_program::ID,
+                        false,
+                    );
+                    layout.push("metadata", metadata_address(&nico.pubkey), true);
+                    layout.push("master_edition", edition_address(&nico.pubkey), false);
+                    layout.push("collection_metadata", metadata_address(group), true);
+                    layout.push("collection_master_edition", edition_address(group), false);
+                }
+            },
+        }
+
+        layout.push("group", *group, true);
+
+        Ok(layout)
+    }
+
+    pub fn account_metas(&self) -> Vec<AccountMeta> {
+        self.accounts
+            .iter()
+            .map(|x| AccountMeta {
+                pubkey: x.pubkey,
+                is_signer: false,
+                is_writable: x.is_writable,
+            })
+            .collect()
+    }
+
+    /// Check the remaining accounts against the layout and return the ones
+    /// it covers. `Positional` expects the accounts in layout order and
+    /// fails on any left over. `Search` is the lookup Nico operations do by
+    /// default: any order, extra accounts ignored.
+    ///
+    /// Pass the result on through the `accounts` of the operation params,
+    /// e.g. [`crate::NicoParams::accounts`], to skip the search.
+    pub fn parse<'a>(
+        &self,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        lookup: NicoAccountLookup,
+    ) -> Result<NicoAccounts<'a>, ProgramError> {
+        let mut accounts = Vec::with_capacity(self.accounts.len());
+
+        match lookup {
+            NicoAccountLookup::Positional => {
+                if remaining_accounts.len() < self.accounts.len() {
+                    let missing = &self.accounts[remaining_accounts.len()];
+                    msg!(
+                        "Account \"{}\" [{}] not found in remaining accounts",
+                        missing.name,
+                        missing.pubkey
+                    );
+                    return Err(Error::MissingRemainingAccount.into());
+                }
+
+                for (spec, account) in self.accounts.iter().zip(remaining_accounts) {
+                    assert_same_pubkeys(spec.name, account, &spec.pubkey)?;
+                    check_writable(spec, account)?;
+                    accounts.push((spec.name, account));
+                }
+
+                let unused = &remaining_accounts[self.accounts.len()..];
+                if !unused.is_empty() {
+                    for account in unused {
+                        msg!("Account [{}] is not used", account.key);
+                    }
+                    return Err(Error::UnusedRemainingAccounts.into());
+                }
+            }
+            NicoAccountLookup::Search => {
+                for spec in &self.accounts {
+                    let account = find_account(&spec.pubkey, remaining_accounts, spec.name)?;
+                    check_writable(spec, account)?;
+                    accounts.push((spec.name, account));
+                }
+            }
+        }
+
+        Ok(NicoAccounts { accounts })
+    }
+}
+
+fn check_writable(spec: &NicoAccountSpec, account: &AccountInfo) -> Result<(), ProgramError> {
+    if spec.is_writable {
+        assert_writable(spec.name, account)?;
+    }
+    Ok(())
+}
+
+// the layout cannot run the on-chain inference, so the token standard
+// must be known
+fn metadata_type(metadata: &MetadataType) -> Result<MetadataType, ProgramError> {
+    match metadata {
+        MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+        x => Ok(*x),
+    }
+}
+
+fn current_token_account(nico: &Nico) -> Result<Pubkey, ProgramError> {
+    match &nico.nico_type {
+        NicoType::Mint {
+            current_token_account: Some(x),
+            ..
+        } => Ok(*x.key),
+        _ => Err(Error::MissingCurrentTokenAccount.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_core::types::UpdateAuthority;
+    use mpl_token_metadata::types::TokenStandard;
+
+    use crate::{
+        testing::{
+            fake_account, fake_core_asset, fake_metadata, fake_mint, fake_nifty_asset, fake_wallet,
+        },
+        CompressedLeaf, NicoTransferParams,
+    };
+
+    use super::*;
+
+    // the accounts a client would pass for the layout, in layout order. an
+    // account in `known` is passed as is, the others are blank
+    fn accounts_for(
+        layout: &NicoAccountLayout,
+        known: &[&'static AccountInfo<'static>],
+    ) -> &'static [AccountInfo<'static>] {
+        let accounts: Vec<_> = layout
+            .accounts
+            .iter()
+            .map(|spec| match known.iter().find(|x| x.key.eq(&spec.pubkey)) {
+                Some(x) => (*x).clone(),
+                None => fake_account(spec.pubkey, Pubkey::new_unique(), vec![]).clone(),
+            })
+            .collect();
+
+        accounts.leak()
+    }
+
+    // checks the transfer layout of `nico` against `expected`, then that
+    // the transfer finds everything it needs in the accounts it lists
+    fn check_transfer_layout(
+        asset_info: &'static AccountInfo<'static>,
+        nico: &Nico<'static>,
+        known: &[&'static AccountInfo<'static>],
+        expected: &[&str],
+    ) {
+        let payer_info = fake_wallet();
+        let recipient_info = fake_wallet();
+
+        let layout =
+            NicoAccountLayout::transfer(nico, recipient_info.key, payer_info.key, None).unwrap();
+        let names: Vec<_> = layout.accounts.iter().map(|x| x.name).collect();
+        assert_eq!(names, expected);
+
+        let remaining_accounts = accounts_for(&layout, known);
+        let accounts = layout
+            .parse(remaining_accounts, NicoAccountLookup::Positional)
+            .unwrap();
+
+        let mut params = NicoTransferParams::new(
+            nico,
+            payer_info,
+            recipient_info,
+            None,
+            &[],
+            remaining_accounts,
+        )
+        .unwrap();
+        params.base.accounts = accounts;
+
+        assert!(nico
+            .transfer_params(asset_info, &params, remaining_accounts)
+            .is_ok());
+    }
+
+    #[test]
+    fn nifty_transfer_layout() {
+        let group_info = fake_nifty_asset(&Pubkey::new_unique(), None);
+        let asset_info = fake_nifty_asset(&Pubkey::new_unique(), Some(group_info.key));
+        let nico = Nico::try_from_accounts(asset_info, None, None, None).unwrap();
+
+        check_transfer_layout(asset_info, &nico, &[group_info], &["group", "nifty_asset"]);
+    }
+
+    #[test]
+    fn core_transfer_layout() {
+        let asset_info = fake_core_asset(UpdateAuthority::None);
+        let nico = Nico::try_from_accounts(asset_info, None, None, None).unwrap();
+
+        check_transfer_layout(
+            asset_info,
+            &nico,
+            &[],
+            &["system_program", "mpl_core_program"],
+        );
+    }
+
+    #[test]
+    fn compressed_transfer_layout() {
+        let merkle_tree_info =
+            fake_account(Pubkey::new_unique(), SPL_ACCOUNT_COMPRESSION_ID, vec![]);
+        let tree_config_info = fake_account(Pubkey::new_unique(), mpl_bubblegum::ID, vec![]);
+        let proof = vec![
+            fake_account(Pubkey::new_unique(), Pubkey::default(), vec![]).clone(),
+            fake_account(Pubkey::new_unique(), Pubkey::default(), vec![]).clone(),
+        ]
+        .leak();
+        let nico = Nico {
+            nico_type: NicoType::Compressed {
+                merkle_tree: merkle_tree_info,
+                tree_config: tree_config_info,
+                proof,
+                leaf: CompressedLeaf {
+                    owner: Pubkey::new_unique(),
+                    delegate: Pubkey::new_unique(),
+                    nonce: 0,
+                    index: 0,
+                    root: [0; 32],
+                    data_hash: [0; 32],
+                    creator_hash: [0; 32],
+                },
+            },
+            pubkey: Pubkey::new_unique(),
+            owner_program: mpl_bubblegum::ID,
+            group: None,
+        };
+
+        check_transfer_layout(
+            merkle_tree_info,
+            &nico,
+            &[merkle_tree_info, tree_config_info, &proof[0], &proof[1]],
+            &[
+                "system_program",
+                "bubblegum_program",
+                "log_wrapper",
+                "compression_program",
+                "leaf_owner",
+                "leaf_delegate",
+                "tree_config",
+                "merkle_tree",
+                "proof",
+                "proof",
+            ],
+        );
+    }
+
+    #[test]
+    fn legacy_transfer_layout() {
+        let (mint_info, metadata_info) =
+            fake_metadata(&spl_token::ID, Some(TokenStandard::NonFungible), None);
+        let nico = Nico::try_from_accounts(
+            mint_info,
+            Some(metadata_info),
+            Some(fake_wallet()),
+            Some(fake_wallet()),
+        )
+        .unwrap();
+
+        check_transfer_layout(
+            mint_info,
+            &nico,
+            &[],
+            &[
+                "system_program",
+                "token_program",
+                "associated_token_program",
+                "target_ata",
+            ],
+        );
+    }
+
+    #[test]
+    fn pnft_transfer_layout() {
+        let (mint_info, metadata_info) = fake_metadata(
+            &spl_token::ID,
+            Some(TokenStandard::ProgrammableNonFungible),
+            None,
+        );
+        let nico = Nico::try_from_accounts(
+            mint_info,
+            Some(metadata_info),
+            Some(fake_wallet()),
+            Some(fake_wallet()),
+        )
+        .unwrap();
+
+        // the transfer reads the rule set from the metadata
+        check_transfer_layout(
+            mint_info,
+            &nico,
+            &[metadata_info],
+            &[
+                "system_program",
+                "mpl_token_metadata",
+                "token_program",
+                "associated_token_program",
+                "auth_rules_program",
+                "metadata",
+                "sysvar_instructions_program",
+                "target_ata",
+                "source_token_record",
+                "target_token_record",
+                "master_edition",
+            ],
+        );
+    }
+
+    #[test]
+    fn token_22_transfer_layout() {
+        let mint_info = fake_mint(&spl_token_2022::ID);
+        let nico = Nico {
+            nico_type: NicoType::Mint {
+                metadata: MetadataType::Token22Extension,
+                current_owner: Some(fake_wallet()),
+                current_token_account: Some(fake_wallet()),
+            },
+            pubkey: *mint_info.key,
+            owner_program: spl_token_2022::ID,
+            group: None,
+        };
+
+        check_transfer_layout(
+            mint_info,
+            &nico,
+            &[],
+            &[
+                "system_program",
+                "token_program",
+                "associated_token_program",
+                "target_ata",
+            ],
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use mpl_core::{
+    accounts::BaseAssetV1,
+    fetch_plugin,
+    instructions::{
+        AddPluginV1Cpi, AddPluginV1InstructionArgs, UpdatePluginV1Cpi,
+        UpdatePluginV1InstructionArgs,
+    },
+    types::{FreezeDelegate, PermanentFreezeDelegate, Plugin, PluginType},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, NicoLockParams};
+
+pub struct LockCoreParams<'a, 'b> {
+    pub mpl_core_program_info: &'a AccountInfo<'a>,
+    pub authority_info: Option<&'a AccountInfo<'a>>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    pub collection_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> LockCoreParams<'a, 'b> {
+    pub fn from_nico_lock_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoLockParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<LockCoreParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params.accounts.find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_core_program_info =
+            params.accounts.find(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(LockCoreParams {
+            mpl_core_program_info,
+            authority_info: params.authority_info,
+            asset_info,
+            collection_asset_opt_info: params.group_asset_opt_info,
+            signer_seeds: params.signer_seeds,
+            payer_info: params.payer_info,
+            system_program_info,
+        })
+    }
+}
+
+pub fn check_and_lock_core(params: LockCoreParams<'_, '_>, lock: bool) -> ProgramResult {
+    let LockCoreParams {
+        mpl_core_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        system_program_info,
+        collection_asset_opt_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("mpl_core_program", mpl_core_program_info, &mpl_core::ID)?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    let plugin = Plugin::FreezeDelegate(FreezeDelegate { frozen: lock });
+
+    let has_freeze_delegate = fetch_plugin::<BaseAssetV1, FreezeDelegate>(
+        asset_info,
+        PluginType::FreezeDelegate,
+    )
+    .is_ok();
+
+    if has_freeze_delegate {
+        // the plugin authority (owner or freeze delegate) flips the flag
+        UpdatePluginV1Cpi {
+            __program: mpl_core_program_info,
+            asset: asset_info,
+            collection: collection_asset_opt_info,
+            payer: payer_info,
+            authority: authority_info,
+            system_program: system_program_info,
+            log_wrapper: None,
+            __args: UpdatePluginV1InstructionArgs { plugin },
+        }
+        .invoke_signed(signer_seeds)?;
+    } else if lock {
+        // the plugin is owner managed, so the owner must sign to add it
+        AddPluginV1Cpi {
+            __program: mpl_core_program_info,
+            asset: asset_info,
+            collection: collection_asset_opt_info,
+            payer: payer_info,
+            authority: authority_info,
+            system_program: system_program_info,
+            log_wrapper: None,
+            __args: AddPluginV1InstructionArgs {
+                plugin,
+                init_authority: None,
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+    // unlocking an asset without a freeze delegate is a no-op
+
+    Ok(())
+}
+
+pub fn is_locked_core(asset_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let frozen = fetch_plugin::<BaseAssetV1, FreezeDelegate>(asset_info, PluginType::FreezeDelegate)
+        .is_ok_and(|(_, plugin, _)| plugin.frozen);
+    let permanently_frozen = fetch_plugin::<BaseAssetV1, PermanentFreezeDelegate>(
+        asset_info,
+        PluginType::PermanentFreezeDelegate,
+    )
+    .is_ok_and(|(_, plugin, _)| plugin.frozen);
+    Ok(frozen || permanently_frozen)
+}
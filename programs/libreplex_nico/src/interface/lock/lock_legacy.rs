@@ -0,0 +1,107 @@
+use mpl_token_metadata::instructions::{FreezeDelegatedAccountCpi, ThawDelegatedAccountCpi};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, AccountState},
+};
+
+use crate::{assertions::assert_same_pubkeys, edition_address, NicoLockParams};
+
+pub struct LockLegacyParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    // must be the delegate of the token account
+    pub delegate_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    token_account_info: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    spl_token_program_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> LockLegacyParams<'a, 'b> {
+    pub fn from_nico_lock_params(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoLockParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<LockLegacyParams<'a, 'b>, ProgramError> {
+        let mpl_token_program_info = params.accounts.find(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let spl_token_program_info = params.accounts.find(
+            &params.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+
+        // the edition is the freeze authority of the mint
+        let edition_info = params.accounts.find(
+            &edition_address(&params.nico_pubkey),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        Ok(LockLegacyParams {
+            mpl_token_program_info,
+            delegate_info: params.authority_info.map_or(params.payer_info, |x| x),
+            asset_info: nico_asset_info,
+            token_account_info: current_token_account,
+            edition: edition_info,
+            spl_token_program_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_lock_legacy(params: LockLegacyParams<'_, '_>, lock: bool) -> ProgramResult {
+    let LockLegacyParams {
+        mpl_token_program_info,
+        delegate_info,
+        asset_info,
+        token_account_info,
+        edition,
+        spl_token_program_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    if lock {
+        FreezeDelegatedAccountCpi {
+            __program: mpl_token_program_info,
+            delegate: delegate_info,
+            token_account: token_account_info,
+            edition,
+            mint: asset_info,
+            token_program: spl_token_program_info,
+        }
+        .invoke_signed(signer_seeds)?;
+    } else {
+        ThawDelegatedAccountCpi {
+            __program: mpl_token_program_info,
+            delegate: delegate_info,
+            token_account: token_account_info,
+            edition,
+            mint: asset_info,
+            token_program: spl_token_program_info,
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+pub fn is_locked_legacy(token_account_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let data = token_account_info.try_borrow_data()?;
+    let token_account = StateWithExtensions::<TokenAccount>::unpack(&data)?.base;
+    Ok(token_account.state == AccountState::Frozen)
+}
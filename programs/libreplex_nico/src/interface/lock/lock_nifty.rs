@@ -0,0 +1,70 @@
+use nifty_asset::{
+    accounts::Asset,
+    instructions::{LockCpi as NiftyLockCpi, UnlockCpi as NiftyUnlockCpi},
+    types::State,
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+};
+
+use crate::{assertions::assert_same_pubkeys, Error, NicoLockParams};
+
+pub struct LockNiftyParams<'a, 'b> {
+    pub nifty_program_info: &'a AccountInfo<'a>,
+    pub signer_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> LockNiftyParams<'a, 'b> {
+    pub fn from_nico_lock_params(
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoLockParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<LockNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info =
+            params.accounts.find(&nifty_asset::ID, remaining_accounts, "nifty_asset")?;
+        Ok(LockNiftyParams {
+            nifty_program_info,
+            signer_info: params.authority_info.map_or(params.payer_info, |x| x),
+            asset_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_lock_nifty(params: LockNiftyParams<'_, '_>, lock: bool) -> ProgramResult {
+    let LockNiftyParams {
+        nifty_program_info,
+        signer_info,
+        asset_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys("nifty_program", nifty_program_info, &nifty_asset::ID)?;
+
+    if lock {
+        NiftyLockCpi {
+            __program: nifty_program_info,
+            asset: asset_info,
+            signer: signer_info,
+        }
+        .invoke_signed(signer_seeds)?;
+    } else {
+        NiftyUnlockCpi {
+            __program: nifty_program_info,
+            asset: asset_info,
+            signer: signer_info,
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+    Ok(())
+}
+
+pub fn is_locked_nifty(asset_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let asset = Asset::try_from(asset_info).map_err(|error| {
+        msg!("Error: {}", error);
+        Error::AssetDeserializationFailed
+    })?;
+    Ok(asset.state == State::Locked)
+}
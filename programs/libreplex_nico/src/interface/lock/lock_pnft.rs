@@ -0,0 +1,212 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_token_metadata::{
+    accounts::{Metadata, TokenRecord},
+    instructions::{LockV1Cpi, LockV1InstructionArgs, UnlockV1Cpi, UnlockV1InstructionArgs},
+    types::{ProgrammableConfig, TokenState},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{
+    assertions::assert_same_pubkeys, edition_address, find_account, metadata_address,
+    token_record_address, Error, NicoLockParams, AUTH_RULES_PROGRAM_ID,
+};
+
+pub struct LockPnftParams<'a, 'b> {
+    pub mpl_token_program_info: &'a AccountInfo<'a>,
+    // the utility or staking delegate of the token record
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    token_owner_info: Option<&'a AccountInfo<'a>>,
+    token_account_info: &'a AccountInfo<'a>,
+    metadata: &'a AccountInfo<'a>,
+    edition: &'a AccountInfo<'a>,
+    token_record_info: &'a AccountInfo<'a>,
+    sysvar_instruction_info: &'a AccountInfo<'a>,
+    spl_token_program_info: &'a AccountInfo<'a>,
+    auth_rules_program_info: &'a AccountInfo<'a>,
+    auth_rules_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> LockPnftParams<'a, 'b> {
+    pub fn from_nico_lock_params(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_owner: Option<&'a AccountInfo<'a>>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoLockParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<LockPnftParams<'a, 'b>, ProgramError> {
+        let system_program_info =
+            params.accounts.find(&system_program::ID, remaining_accounts, "system_program")?;
+
+        let mpl_token_program_info = params.accounts.find(
+            &mpl_token_metadata::ID,
+            remaining_accounts,
+            "mpl_token_metadata",
+        )?;
+
+        let spl_token_program_info = params.accounts.find(
+            &params.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+
+        let auth_rules_program_info = params.accounts.find(
+            &AUTH_RULES_PROGRAM_ID,
+            remaining_accounts,
+            "auth_rules_program",
+        )?;
+
+        let sysvar_instruction_info = params.accounts.find(
+            &sysvar_instructions_program::ID,
+            remaining_accounts,
+            "sysvar_instructions_program",
+        )?;
+
+        let metadata_info = params.accounts.find(
+            &metadata_address(&params.nico_pubkey),
+            remaining_accounts,
+            "metadata",
+        )?;
+
+        let metadata = Metadata::safe_deserialize(&metadata_info.try_borrow_data()?)
+            .map_err(|error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            })?;
+
+        let auth_rules_info = match metadata.programmable_config {
+            Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+            None => None,
+        }
+        .map(|x| params.accounts.find(&x, remaining_accounts, "auth_rule"))
+        .transpose()?;
+
+        let edition_info = params.accounts.find(
+            &edition_address(&params.nico_pubkey),
+            remaining_accounts,
+            "master_edition",
+        )?;
+
+        let token_record_info = params.accounts.find(
+            &token_record_address(&params.nico_pubkey, current_token_account.key),
+            remaining_accounts,
+            "token_record",
+        )?;
+
+        Ok(LockPnftParams {
+            mpl_token_program_info,
+            authority_info: params.authority_info.map_or(params.payer_info, |x| x),
+            asset_info: nico_asset_info,
+            payer_info: params.payer_info,
+            system_program_info,
+            token_owner_info: current_owner,
+            token_account_info: current_token_account,
+            metadata: metadata_info,
+            edition: edition_info,
+            token_record_info,
+            sysvar_instruction_info,
+            spl_token_program_info,
+            auth_rules_program_info,
+            auth_rules_info,
+            signer_seeds: params.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_lock_pnft(params: LockPnftParams<'_, '_>, lock: bool) -> ProgramResult {
+    let LockPnftParams {
+        mpl_token_program_info,
+        authority_info,
+        asset_info,
+        payer_info,
+        system_program_info,
+        token_owner_info,
+        token_account_info,
+        metadata,
+        edition,
+        token_record_info,
+        sysvar_instruction_info,
+        spl_token_program_info,
+        auth_rules_program_info,
+        auth_rules_info,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "mpl_token_metadata",
+        mpl_token_program_info,
+        &mpl_token_metadata::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    if lock {
+        LockV1Cpi {
+            __program: mpl_token_program_info,
+            authority: authority_info,
+            token_owner: token_owner_info,
+            token: token_account_info,
+            mint: asset_info,
+            metadata,
+            edition: Some(edition),
+            token_record: Some(token_record_info),
+            payer: payer_info,
+            system_program: system_program_info,
+            sysvar_instructions: sysvar_instruction_info,
+            spl_token_program: Some(spl_token_program_info),
+            authorization_rules_program: Some(auth_rules_program_info),
+            authorization_rules: auth_rules_info,
+            __args: LockV1InstructionArgs {
+                authorization_data: None,
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+    } else {
+        UnlockV1Cpi {
+            __program: mpl_token_program_info,
+            authority: authority_info,
+            token_owner: token_owner_info,
+            token: token_account_info,
+            mint: asset_info,
+            metadata,
+            edition: Some(edition),
+            token_record: Some(token_record_info),
+            payer: payer_info,
+            system_program: system_program_info,
+            sysvar_instructions: sysvar_instruction_info,
+            spl_token_program: Some(spl_token_program_info),
+            authorization_rules_program: Some(auth_rules_program_info),
+            authorization_rules: auth_rules_info,
+            __args: UnlockV1InstructionArgs {
+                authorization_data: None,
+            },
+        }
+        .invoke_signed(signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+pub fn is_locked_pnft<'a>(
+    mint_info: &'a AccountInfo<'a>,
+    token_account_info: &'a AccountInfo<'a>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<bool, ProgramError> {
+    let token_record_info = find_account(
+        &token_record_address(mint_info.key, token_account_info.key),
+        remaining_accounts,
+        "token_record",
+    )?;
+    let token_record = TokenRecord::safe_deserialize(&token_record_info.try_borrow_data()?)
+        .map_err(|error| {
+            msg!("Error: {}", error);
+            Error::DeserializationError
+        })?;
+    Ok(token_record.state == TokenState::Locked)
+}
@@ -0,0 +1,274 @@
+pub mod lock_core;
+pub mod lock_legacy;
+pub mod lock_nifty;
+pub mod lock_pnft;
+
+use lock_core::{check_and_lock_core, is_locked_core, LockCoreParams};
+use lock_legacy::{check_and_lock_legacy, is_locked_legacy, LockLegacyParams};
+use lock_nifty::{check_and_lock_nifty, is_locked_nifty, LockNiftyParams};
+use lock_pnft::{check_and_lock_pnft, is_locked_pnft, LockPnftParams};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+};
+
+use crate::{Error, MetadataType, Nico, NicoParams, NicoType};
+
+pub type NicoLockParams<'a, 'b> = NicoParams<'a, 'b>;
+
+impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Lock (freeze in wallet) the asset. The authority (or the payer if no
+    /// authority is given) must be the owner or the lock / freeze delegate.
+    pub fn lock(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        self.set_locked(
+            asset_info,
+            payer,
+            authority,
+            remaining_accounts,
+            signer_seeds,
+            true,
+        )
+    }
+
+    /// Unlock an asset previously locked with [`Nico::lock`].
+    pub fn unlock(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> ProgramResult {
+        self.set_locked(
+            asset_info,
+            payer,
+            authority,
+            remaining_accounts,
+            signer_seeds,
+            false,
+        )
+    }
+
+    /// Whether the asset is currently locked in the owner's wallet.
+    pub fn is_locked(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<bool, ProgramError> {
+        match &self.nico_type {
+            NicoType::Nifty => is_locked_nifty(asset_info),
+            NicoType::MxCore => is_locked_core(asset_info),
+            NicoType::Compressed { .. } => {
+                msg!("Compressed Nicos cannot be locked");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint {
+                metadata,
+                current_token_account,
+                ..
+            } => {
+                let current_token_account =
+                    current_token_account.ok_or(Error::MissingCurrentTokenAccount)?;
+                match metadata {
+                    MetadataType::Mxprogrammable => {
+                        is_locked_pnft(asset_info, current_token_account, remaining_accounts)
+                    }
+                    // everything else is locked by freezing the token account
+                    _ => is_locked_legacy(current_token_account),
+                }
+            }
+        }
+    }
+
+    fn set_locked(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        payer: &'a AccountInfo<'a>,
+        authority: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        lock: bool,
+    ) -> ProgramResult {
+        let params = NicoLockParams::new(self, payer, authority, signer_seeds, remaining_accounts)?;
+
+        self.set_locked_with_params(asset_info, params, remaining_accounts, lock)
+    }
+
+    /// Same as [`Nico::lock`] and [`Nico::unlock`], with the params built by
+    /// the caller, e.g. to set the accounts parsed by
+    /// [`crate::NicoAccountLayout::parse`].
+    pub fn set_locked_with_params(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        params: NicoLockParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        lock: bool,
+    ) -> ProgramResult {
+        match &self.nico_type {
+            NicoType::Nifty => {
+                let nifty_params =
+                    LockNiftyParams::from_nico_lock_params(asset_info, &params, remaining_accounts)?;
+                check_and_lock_nifty(nifty_params, lock)
+            }
+            NicoType::MxCore => {
+                let core_params =
+                    LockCoreParams::from_nico_lock_params(asset_info, &params, remaining_accounts)?;
+                check_and_lock_core(core_params, lock)
+            }
+            NicoType::Compressed { .. } => {
+                msg!("Compressed Nicos cannot be locked");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint {
+                metadata,
+                current_owner,
+                current_token_account,
+            } => {
+                let metadata = match metadata {
+                    // token standard not set on the metadata. work it out from the accounts
+                    MetadataType::Unknown => {
+                        MetadataType::infer(asset_info, *current_token_account, remaining_accounts)?
+                    }
+                    x => *x,
+                };
+                match metadata {
+                    MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+                    MetadataType::Token22Extension => {
+                        msg!("Locking Token-2022 metadata extension Nicos is not supported");
+                        Err(Error::UnsupportedAssetType.into())
+                    }
+                    MetadataType::MxNonProgrammable => {
+                        let legacy_params = LockLegacyParams::from_nico_lock_params(
+                            asset_info,
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_lock_legacy(legacy_params, lock)
+                    }
+                    MetadataType::Mxprogrammable => {
+                        let pnft_params = LockPnftParams::from_nico_lock_params(
+                            asset_info,
+                            *current_owner,
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            &params,
+                            remaining_accounts,
+                        )?;
+                        check_and_lock_pnft(pnft_params, lock)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_core::types::{FreezeDelegate, PermanentFreezeDelegate, Plugin, UpdateAuthority};
+    use mpl_token_metadata::types::{TokenStandard, TokenState};
+    use solana_program::pubkey::Pubkey;
+
+    use crate::testing::{
+        fake_core_asset_with_plugins, fake_metadata, fake_token_account, fake_token_record,
+    };
+
+    use super::*;
+
+    // a legacy NFT or pNFT held in a token account, frozen or not
+    fn metaplex_nico(
+        token_standard: TokenStandard,
+        frozen: bool,
+    ) -> (&'static AccountInfo<'static>, Nico<'static>) {
+        let (mint_info, metadata_info) = fake_metadata(&spl_token::ID, Some(token_standard), None);
+        let token_account_info =
+            fake_token_account(mint_info.key, &Pubkey::new_unique(), &spl_token::ID, frozen);
+        let nico = Nico::try_from_accounts(
+            mint_info,
+            Some(metadata_info),
+            None,
+            Some(token_account_info),
+        )
+        .unwrap();
+
+        (mint_info, nico)
+    }
+
+    #[test]
+    fn core_asset_is_locked_by_either_freeze_plugin() {
+        let cases = [
+            (vec![], false),
+            (
+                vec![Plugin::FreezeDelegate(FreezeDelegate { frozen: false })],
+                false,
+            ),
+            (
+                vec![Plugin::FreezeDelegate(FreezeDelegate { frozen: true })],
+                true,
+            ),
+            (
+                vec![Plugin::PermanentFreezeDelegate(PermanentFreezeDelegate {
+                    frozen: true,
+                })],
+                true,
+            ),
+        ];
+
+        for (plugins, locked) in cases {
+            // core sizes the asset for an update authority with an address
+            let asset_info = fake_core_asset_with_plugins(
+                UpdateAuthority::Address(Pubkey::new_unique()),
+                plugins.clone(),
+            );
+            let nico = Nico::try_from_accounts(asset_info, None, None, None).unwrap();
+
+            assert_eq!(nico.is_locked(asset_info, &[]), Ok(locked), "{:?}", plugins);
+        }
+    }
+
+    #[test]
+    fn legacy_nft_is_locked_by_frozen_token_account() {
+        for frozen in [false, true] {
+            let (mint_info, nico) = metaplex_nico(TokenStandard::NonFungible, frozen);
+
+            assert_eq!(nico.is_locked(mint_info, &[]), Ok(frozen));
+        }
+    }
+
+    #[test]
+    fn pnft_is_locked_by_token_record() {
+        let cases = [
+            (TokenState::Unlocked, false),
+            (TokenState::Locked, true),
+            // listed pNFTs are frozen, but not locked
+            (TokenState::Listed, false),
+        ];
+
+        for (state, locked) in cases {
+            // pNFT token accounts are always frozen
+            let (mint_info, nico) = metaplex_nico(TokenStandard::ProgrammableNonFungible, true);
+            let NicoType::Mint {
+                current_token_account: Some(token_account_info),
+                ..
+            } = nico.nico_type
+            else {
+                unreachable!()
+            };
+            let token_record_info =
+                fake_token_record(mint_info.key, token_account_info.key, state.clone());
+            let remaining_accounts = vec![token_record_info.clone()].leak();
+
+            assert_eq!(
+                nico.is_locked(mint_info, remaining_accounts),
+                Ok(locked),
+                "{:?}",
+                state
+            );
+        }
+    }
+}
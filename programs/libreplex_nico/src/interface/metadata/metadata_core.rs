@@ -0,0 +1,85 @@
+use mpl_core::{
+    accounts::{BaseAssetV1, BaseCollectionV1},
+    fetch_plugin,
+    types::{Attributes, PluginType, Royalties},
+};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{find_account, Error, NicoAttribute, NicoCreator, NicoMetadata};
+
+/// Royalties plugin of a core asset, or of its collection if the asset has
+/// none of its own. Royalties are usually set on the collection, so the
+/// collection account must be in the remaining accounts for assets in one.
+pub fn core_royalties<'a>(
+    asset_info: &'a AccountInfo<'a>,
+    collection: Option<Pubkey>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<Option<Royalties>, ProgramError> {
+    if let Ok((_, royalties, _)) =
+        fetch_plugin::<BaseAssetV1, Royalties>(asset_info, PluginType::Royalties)
+    {
+        return Ok(Some(royalties));
+    }
+
+    let collection_info = match collection {
+        Some(x) => find_account(&x, remaining_accounts, "collection")?,
+        None => return Ok(None),
+    };
+
+    Ok(
+        fetch_plugin::<BaseCollectionV1, Royalties>(collection_info, PluginType::Royalties)
+            .ok()
+            .map(|(_, royalties, _)| royalties),
+    )
+}
+
+pub fn core_metadata<'a>(
+    asset_info: &'a AccountInfo<'a>,
+    collection: Option<Pubkey>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<NicoMetadata, ProgramError> {
+    let asset = BaseAssetV1::try_from(asset_info).map_err(|error| {
+        msg!("Error: {}", error);
+        Error::AssetDeserializationFailed
+    })?;
+
+    let (seller_fee_bps, creators) =
+        match core_royalties(asset_info, collection, remaining_accounts)? {
+            Some(royalties) => (
+                royalties.basis_points,
+                royalties
+                    .creators
+                    .into_iter()
+                    .map(|creator| NicoCreator {
+                        address: creator.address,
+                        verified: false,
+                        share: creator.percentage,
+                    })
+                    .collect(),
+            ),
+            None => (0, vec![]),
+        };
+
+    let attributes =
+        match fetch_plugin::<BaseAssetV1, Attributes>(asset_info, PluginType::Attributes) {
+            Ok((_, attributes, _)) => attributes
+                .attribute_list
+                .into_iter()
+                .map(|attribute| NicoAttribute {
+                    trait_type: attribute.key,
+                    value: attribute.value,
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+
+    Ok(NicoMetadata {
+        name: asset.name,
+        // core assets have no symbol
+        symbol: String::new(),
+        uri: asset.uri,
+        seller_fee_bps,
+        creators,
+        attributes,
+    })
+}
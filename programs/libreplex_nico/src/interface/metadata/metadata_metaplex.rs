@@ -0,0 +1,41 @@
+use mpl_token_metadata::accounts::Metadata;
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError};
+
+use crate::{find_account, metadata_address, Error, NicoCreator, NicoMetadata};
+
+pub fn metaplex_metadata<'a>(
+    mint_info: &'a AccountInfo<'a>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<NicoMetadata, ProgramError> {
+    let metadata_info = find_account(
+        &metadata_address(mint_info.key),
+        remaining_accounts,
+        "metadata",
+    )?;
+
+    let metadata = Metadata::safe_deserialize(&metadata_info.try_borrow_data()?)
+        .map_err(|error| {
+            msg!("Error: {}", error);
+            Error::DeserializationError
+        })?;
+
+    // attributes are only stored off-chain, in the json at the uri
+    Ok(NicoMetadata {
+        // fixed size fields are padded with null bytes
+        name: metadata.name.trim_end_matches('\0').to_string(),
+        symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+        uri: metadata.uri.trim_end_matches('\0').to_string(),
+        seller_fee_bps: metadata.seller_fee_basis_points,
+        creators: metadata
+            .creators
+            .unwrap_or_default()
+            .into_iter()
+            .map(|creator| NicoCreator {
+                address: creator.address,
+                verified: creator.verified,
+                share: creator.share,
+            })
+            .collect(),
+        attributes: vec![],
+    })
+}
@@ -0,0 +1,59 @@
+use nifty_asset::{
+    extensions::{Attributes, Creators, Metadata, Royalties},
+    state::Asset,
+    ZeroCopy,
+};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError};
+
+use crate::{Error, NicoAttribute, NicoCreator, NicoMetadata};
+
+pub fn nifty_metadata(asset_info: &AccountInfo) -> Result<NicoMetadata, ProgramError> {
+    let data = asset_info.try_borrow_data()?;
+    if data.len() < Asset::LEN {
+        msg!("Nifty asset {} is too small", asset_info.key);
+        return Err(Error::InvalidNiftyAsset.into());
+    }
+    let asset = Asset::load(&data);
+
+    let (symbol, uri) = Asset::get::<Metadata>(&data)
+        .map(|x| (x.symbol.as_str().to_string(), x.uri.as_str().to_string()))
+        .unwrap_or_default();
+
+    let seller_fee_bps = match Asset::get::<Royalties>(&data) {
+        Some(x) => u16::try_from(*x.basis_points).map_err(|_| Error::NumericalOverflow)?,
+        None => 0,
+    };
+
+    let creators = Asset::get::<Creators>(&data)
+        .map(|x| {
+            x.values
+                .iter()
+                .map(|creator| NicoCreator {
+                    address: creator.address,
+                    verified: creator.verified.into(),
+                    share: creator.share,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let attributes = Asset::get::<Attributes>(&data)
+        .map(|x| {
+            x.iter()
+                .map(|t| NicoAttribute {
+                    trait_type: t.name.as_str().to_string(),
+                    value: t.value.as_str().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(NicoMetadata {
+        name: asset.name.to_string(),
+        symbol,
+        uri,
+        seller_fee_bps,
+        creators,
+        attributes,
+    })
+}
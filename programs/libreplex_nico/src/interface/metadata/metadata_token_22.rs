@@ -0,0 +1,66 @@
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+use spl_type_length_value::state::{TlvState, TlvStateBorrowed};
+
+use crate::{find_account, Error, NicoAttribute, NicoMetadata};
+
+pub fn token_22_metadata<'a>(
+    mint_info: &'a AccountInfo<'a>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<NicoMetadata, ProgramError> {
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    let metadata_address = mint
+        .get_extension::<MetadataPointer>()
+        .ok()
+        .and_then(|x| Option::<Pubkey>::from(x.metadata_address))
+        .ok_or_else(|| {
+            msg!("Mint {} has no metadata pointer", mint_info.key);
+            Error::MissingMetadataAccount
+        })?;
+
+    let token_metadata = if metadata_address.eq(mint_info.key) {
+        // metadata lives in the mint itself
+        mint.get_variable_len_extension::<TokenMetadata>()
+    } else {
+        let metadata_info = find_account(&metadata_address, remaining_accounts, "metadata")?;
+        let data = metadata_info.try_borrow_data()?;
+        TlvStateBorrowed::unpack(&data)
+            .and_then(|tlv| tlv.get_first_variable_len_value::<TokenMetadata>())
+    }
+    .map_err(|error| {
+        msg!("Error: {}", error);
+        Error::DeserializationError
+    })?;
+
+    // guard against metadata belonging to a different mint
+    if !token_metadata.mint.eq(mint_info.key) {
+        msg!(
+            "Token metadata mint [{}] does not match mint [{}]",
+            token_metadata.mint,
+            mint_info.key
+        );
+        return Err(Error::AccountMismatch.into());
+    }
+
+    // royalties and creators are not part of the token metadata interface
+    Ok(NicoMetadata {
+        name: token_metadata.name,
+        symbol: token_metadata.symbol,
+        uri: token_metadata.uri,
+        seller_fee_bps: 0,
+        creators: vec![],
+        attributes: token_metadata
+            .additional_metadata
+            .into_iter()
+            .map(|(trait_type, value)| NicoAttribute { trait_type, value })
+            .collect(),
+    })
+}
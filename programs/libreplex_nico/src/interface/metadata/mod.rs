@@ -0,0 +1,72 @@
+pub mod metadata_core;
+pub mod metadata_metaplex;
+pub mod metadata_nifty;
+pub mod metadata_token_22;
+
+use metadata_core::core_metadata;
+use metadata_metaplex::metaplex_metadata;
+use metadata_nifty::nifty_metadata;
+use metadata_token_22::token_22_metadata;
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{Error, MetadataType, Nico, NicoType};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NicoCreator {
+    pub address: Pubkey,
+    // always false for core assets, which have no creator verification
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NicoAttribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// Standard independent view of the content of a Nico.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NicoMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_bps: u16,
+    pub creators: Vec<NicoCreator>,
+    pub attributes: Vec<NicoAttribute>,
+}
+
+impl<'a: 'c, 'c> Nico<'a> {
+    /// Read the name, symbol, uri, royalties, creators and attributes of the
+    /// asset. Metaplex mints need their metadata account and Token-2022 mints
+    /// with an external metadata account need that account in the remaining
+    /// accounts, as do core assets in a collection without royalties of
+    /// their own. Fields a standard does not store are left empty.
+    pub fn metadata(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<NicoMetadata, ProgramError> {
+        match &self.nico_type {
+            NicoType::Nifty => nifty_metadata(asset_info),
+            NicoType::MxCore => core_metadata(asset_info, self.group, remaining_accounts),
+            NicoType::Compressed { .. } => {
+                msg!("Compressed Nicos only store a hash of their metadata");
+                Err(Error::UnsupportedAssetType.into())
+            }
+            NicoType::Mint { metadata, .. } => match metadata {
+                MetadataType::Token22Extension => {
+                    token_22_metadata(asset_info, remaining_accounts)
+                }
+                // the token standard does not change where the metadata lives
+                MetadataType::Unknown
+                | MetadataType::MxNonProgrammable
+                | MetadataType::Mxprogrammable => {
+                    metaplex_metadata(asset_info, remaining_accounts)
+                }
+            },
+        }
+    }
+}
@@ -1,6 +1,40 @@
 pub mod nico;
 pub use nico::*;
 
+pub mod account;
+pub use account::*;
+
+pub mod params;
+pub use params::*;
+
 pub mod transfer;
 pub use transfer::*;
 
+pub mod burn;
+pub use burn::*;
+
+pub mod lock;
+pub use lock::*;
+
+pub mod delegate;
+pub use delegate::*;
+
+pub mod metadata;
+pub use metadata::*;
+
+pub mod royalties;
+pub use royalties::*;
+
+pub mod create;
+pub use create::*;
+
+pub mod group;
+pub use group::*;
+
+pub mod owner;
+
+pub mod derivations;
+pub use derivations::*;
+
+pub mod layout;
+pub use layout::*;
@@ -1,15 +1,26 @@
 use anchor_lang::Key;
+use mpl_bubblegum::utils::get_asset_id;
 use mpl_core::accounts::BaseAssetV1;
-use mpl_core::asset;
 use mpl_core::types::UpdateAuthority;
 use mpl_token_metadata::accounts::Metadata;
 use mpl_token_metadata::types::TokenStandard;
 use nifty_asset::accounts::Asset;
 use solana_program::msg;
+use solana_program::program_error::ProgramError;
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+use spl_token_2022::extension::metadata_pointer::MetadataPointer;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint;
+use spl_token_group_interface::state::TokenGroupMember;
+use spl_token_metadata_interface::state::TokenMetadata;
+use spl_type_length_value::state::{TlvState, TlvStateBorrowed};
 
-use crate::{find_account, find_account_data};
+use crate::{
+    assert_pda, assert_program_owner, edition_address, find_account, metadata_address,
+    token_record_address, Error,
+};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MetadataType {
     // if not enough accounts are provided at construction time
     Unknown,
@@ -21,6 +32,69 @@ pub enum MetadataType {
     Mxprogrammable,
 }
 
+impl MetadataType {
+    /// Infer the token standard of a Metaplex mint whose metadata has no
+    /// token standard set, using the mint supply and decimals and the
+    /// edition and token record accounts found in the remaining accounts.
+    pub fn infer<'a>(
+        mint_info: &'a AccountInfo<'a>,
+        current_token_account: Option<&'a AccountInfo<'a>>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<MetadataType, ProgramError> {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?.base;
+        drop(mint_data);
+
+        // anything other than a single, indivisible token is not an NFT
+        if mint.decimals != 0 || mint.supply != 1 {
+            msg!(
+                "Mint {} has supply {} and decimals {}. Not a non-fungible",
+                mint_info.key,
+                mint.supply,
+                mint.decimals
+            );
+            return Err(Error::UnsupportedTokenStandard.into());
+        }
+
+        // master editions and print editions share the same PDA
+        let edition_info = find_account(
+            &edition_address(mint_info.key),
+            remaining_accounts,
+            "edition",
+        )
+        .map_err(|_| Error::InsufficientAccountsToClassify)?;
+
+        if edition_info.data_is_empty() || !edition_info.owner.eq(&mpl_token_metadata::ID) {
+            msg!("Mint {} has no edition. Not a non-fungible", mint_info.key);
+            return Err(Error::UnsupportedTokenStandard.into());
+        }
+
+        // only programmable non-fungibles have token records
+        let has_token_record = current_token_account
+            .map(|token_account| token_record_address(mint_info.key, token_account.key))
+            .and_then(|token_record| remaining_accounts.iter().find(|x| x.key.eq(&token_record)))
+            .is_some_and(|x| !x.data_is_empty() && x.owner.eq(&mpl_token_metadata::ID));
+
+        Ok(if has_token_record {
+            MetadataType::Mxprogrammable
+        } else {
+            MetadataType::MxNonProgrammable
+        })
+    }
+}
+
+/// Leaf data of a Bubblegum compressed NFT, as returned by the DAS API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompressedLeaf {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+}
+
 pub enum NicoType<'a> {
     Nifty,
     MxCore,
@@ -29,6 +103,13 @@ pub enum NicoType<'a> {
         current_owner: Option<&'a AccountInfo<'a>>,
         current_token_account: Option<&'a AccountInfo<'a>>,
     },
+    Compressed {
+        merkle_tree: &'a AccountInfo<'a>,
+        tree_config: &'a AccountInfo<'a>,
+        // proof path accounts, from the leaf up to the canopy
+        proof: &'a [AccountInfo<'a>],
+        leaf: CompressedLeaf,
+    },
 }
 
 pub struct Nico<'f> {
@@ -45,29 +126,82 @@ pub struct AccountData<'f> {
 }
 
 impl<'f> Nico<'f> {
-
-
+    /// Classify an asset account. Panics if the asset cannot be classified,
+    /// use [`Nico::try_from_accounts`] to handle the error instead.
     pub fn from_raw_data(
         asset_info: &'f AccountInfo<'f>,
         metadata_data: Option<&'f AccountInfo<'f>>,
         current_owner: Option<&'f AccountInfo<'f>>,
         current_token_account: Option<&'f AccountInfo<'f>>
     ) -> Nico<'f> {
+        Nico::try_from_accounts(asset_info, metadata_data, current_owner, current_token_account)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Build a Nico for a Bubblegum compressed NFT. The asset id is derived
+    /// from the merkle tree and the leaf nonce.
+    pub fn from_compressed(
+        merkle_tree: &'f AccountInfo<'f>,
+        tree_config: &'f AccountInfo<'f>,
+        proof: &'f [AccountInfo<'f>],
+        leaf: CompressedLeaf,
+    ) -> Result<Nico<'f>, ProgramError> {
+        assert_program_owner(
+            "merkle_tree",
+            merkle_tree,
+            &mpl_bubblegum::programs::SPL_ACCOUNT_COMPRESSION_ID,
+        )?;
+        assert_program_owner("tree_config", tree_config, &mpl_bubblegum::ID)?;
+        assert_pda(
+            "tree_config",
+            tree_config,
+            &mpl_bubblegum::ID,
+            &[merkle_tree.key.as_ref()],
+        )?;
+
+        Ok(Nico {
+            nico_type: NicoType::Compressed {
+                merkle_tree,
+                tree_config,
+                proof,
+                leaf,
+            },
+            pubkey: get_asset_id(merkle_tree.key, leaf.nonce),
+            owner_program: mpl_bubblegum::ID,
+            // collection membership is hashed into the leaf and cannot be read here
+            group: None,
+        })
+    }
+
+    /// Classify an asset account, returning an error if the owner program,
+    /// token standard or account data is not supported.
+    pub fn try_from_accounts(
+        asset_info: &'f AccountInfo<'f>,
+        metadata_data: Option<&'f AccountInfo<'f>>,
+        current_owner: Option<&'f AccountInfo<'f>>,
+        current_token_account: Option<&'f AccountInfo<'f>>
+    ) -> Result<Nico<'f>, Error> {
         
         let asset_owner_program = asset_info.owner;
         let pubkey = asset_info.key();
         if asset_owner_program.eq(&nifty_asset::ID) {
-            let nifty_asset = Asset::try_from(asset_info).unwrap();
-            Nico {
+            let nifty_asset = Asset::try_from(asset_info).map_err(|error| {
+                msg!("Error: {}", error);
+                Error::AssetDeserializationFailed
+            })?;
+            Ok(Nico {
                 nico_type: NicoType::Nifty,
                 group: nifty_asset.group.to_option(),
                 pubkey,
                 owner_program: *asset_owner_program
-            }
+            })
         } else if asset_owner_program.eq(&mpl_core::ID) {
-            let core_asset = BaseAssetV1::try_from(asset_info).unwrap();
+            let core_asset = BaseAssetV1::try_from(asset_info).map_err(|error| {
+                msg!("Error: {}", error);
+                Error::AssetDeserializationFailed
+            })?;
             msg!("{:?}", core_asset.update_authority);
-            Nico {
+            Ok(Nico {
                 nico_type: NicoType::MxCore,
                 group: match core_asset.update_authority {
                     UpdateAuthority::None => None,
@@ -76,55 +210,174 @@ impl<'f> Nico<'f> {
                 },
                 pubkey,
                 owner_program: mpl_core::ID
-            }
+            })
         } else if asset_owner_program.eq(&spl_token_2022::ID)
             || asset_owner_program.eq(&spl_token::ID)
         {
-            if let Some(md) = metadata_data {
-                // ok we have a metadata account in the context.
-                // try and deserialize
-                let metadata_obj = Metadata::safe_deserialize(&(*md.data).borrow()).ok();
-                if let Some(m) = metadata_obj {
-                    return Nico {
-                        nico_type: match m.token_standard {
-                            Some(TokenStandard::ProgrammableNonFungible) => NicoType::Mint {
-                                metadata: MetadataType::Mxprogrammable,
-                                current_owner,
-                                current_token_account,
-                            },
-                            Some(TokenStandard::NonFungible) => NicoType::Mint {
-                                metadata: MetadataType::MxNonProgrammable,
-                                current_owner,
-                                current_token_account,
-                            },
-                            None => NicoType::Mint {
-                                metadata: MetadataType::Unknown,
-                                current_owner,
-                                current_token_account,
-                            },
-                            _ => {
-                                panic!("Unsupported Mx token standard");
-                            },
-                        },
-                        pubkey,
-                        group: match m.collection {
-                            Some(x) => {
-                                if x.verified {
-                                    Some(x.key)
-                                } else {
-                                    None
-                                }
-                            }
-                            None => None,
-                        },
-                        owner_program: *asset_owner_program
-                    };
-                };
+            if asset_owner_program.eq(&spl_token_2022::ID) {
+                if let Some(nico) = Nico::try_from_token_22_extensions(
+                    asset_info,
+                    metadata_data,
+                    current_owner,
+                    current_token_account,
+                )? {
+                    return Ok(nico);
+                }
+            }
+            let md = metadata_data.ok_or_else(|| {
+                msg!("No metadata account provided for mint {}", pubkey);
+                Error::MissingMetadataAccount
+            })?;
+            // guard against the metadata of another mint, which would lend
+            // this mint its token standard and collection
+            if !md.key.eq(&metadata_address(&pubkey)) || !md.owner.eq(&mpl_token_metadata::ID) {
+                msg!("Account [{}] is not the metadata of mint {}", md.key, pubkey);
+                return Err(Error::InvalidMetadataAccount);
+            }
+            // ok we have a metadata account in the context.
+            // try and deserialize
+            let m = Metadata::safe_deserialize(&(*md.data).borrow()).map_err(|error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            })?;
+            if !m.mint.eq(&pubkey) {
+                msg!("Metadata mint [{}] does not match mint [{}]", m.mint, pubkey);
+                return Err(Error::InvalidMetadataAccount);
             }
-            panic!("No metadata account provided in remaining accounts");
+            Ok(Nico {
+                nico_type: match m.token_standard {
+                    Some(TokenStandard::ProgrammableNonFungible) => NicoType::Mint {
+                        metadata: MetadataType::Mxprogrammable,
+                        current_owner,
+                        current_token_account,
+                    },
+                    Some(TokenStandard::NonFungible) => NicoType::Mint {
+                        metadata: MetadataType::MxNonProgrammable,
+                        current_owner,
+                        current_token_account,
+                    },
+                    None => NicoType::Mint {
+                        metadata: MetadataType::Unknown,
+                        current_owner,
+                        current_token_account,
+                    },
+                    Some(x) => {
+                        msg!("Unsupported Mx token standard {:?}", x);
+                        return Err(Error::UnsupportedTokenStandard);
+                    },
+                },
+                pubkey,
+                group: match m.collection {
+                    Some(x) => {
+                        if x.verified {
+                            Some(x.key)
+                        } else {
+                            None
+                        }
+                    }
+                    None => None,
+                },
+                owner_program: *asset_owner_program
+            })
         } else {
-            panic!("Unexpected account owner")
+            msg!("Unexpected account owner {}", asset_owner_program);
+            Err(Error::UnexpectedAssetOwner)
         }
     }
 
+    /// Classify a Token-2022 mint whose metadata is exposed through the
+    /// metadata pointer extension. Returns `None` if the mint has no metadata
+    /// pointer or points to a Metaplex metadata account, so that the caller
+    /// can fall back to the Metaplex classification.
+    fn try_from_token_22_extensions(
+        asset_info: &'f AccountInfo<'f>,
+        metadata_data: Option<&'f AccountInfo<'f>>,
+        current_owner: Option<&'f AccountInfo<'f>>,
+        current_token_account: Option<&'f AccountInfo<'f>>
+    ) -> Result<Option<Nico<'f>>, Error> {
+        let pubkey = asset_info.key();
+        let mint_data = asset_info.try_borrow_data().map_err(|error| {
+            msg!("Error: {}", error);
+            Error::AssetDeserializationFailed
+        })?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data).map_err(|error| {
+            msg!("Error: {}", error);
+            Error::AssetDeserializationFailed
+        })?;
+
+        let metadata_address = match mint
+            .get_extension::<MetadataPointer>()
+            .ok()
+            .and_then(|x| Option::<Pubkey>::from(x.metadata_address))
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        let token_metadata = if metadata_address.eq(&pubkey) {
+            // metadata lives in the mint itself
+            mint.get_variable_len_extension::<TokenMetadata>()
+        } else {
+            let md = metadata_data
+                .filter(|x| x.key.eq(&metadata_address))
+                .ok_or_else(|| {
+                    msg!(
+                        "Metadata account [{}] for mint {} not provided",
+                        metadata_address,
+                        pubkey
+                    );
+                    Error::MissingMetadataAccount
+                })?;
+            if md.owner.eq(&mpl_token_metadata::ID) {
+                return Ok(None);
+            }
+            let data = md.try_borrow_data().map_err(|error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            })?;
+            TlvStateBorrowed::unpack(&data)
+                .and_then(|tlv| tlv.get_first_variable_len_value::<TokenMetadata>())
+        }
+        .map_err(|error| {
+            msg!("Error: {}", error);
+            Error::DeserializationError
+        })?;
+
+        // guard against metadata belonging to a different mint
+        if !token_metadata.mint.eq(&pubkey) {
+            msg!(
+                "Token metadata mint [{}] does not match mint [{}]",
+                token_metadata.mint,
+                pubkey
+            );
+            return Err(Error::AccountMismatch);
+        }
+
+        let group = match mint.get_extension::<TokenGroupMember>() {
+            Ok(member) => {
+                // guard against a member extension copied from another mint
+                if !member.mint.eq(&pubkey) {
+                    msg!(
+                        "Group member mint [{}] does not match mint [{}]",
+                        member.mint,
+                        pubkey
+                    );
+                    return Err(Error::AccountMismatch);
+                }
+                Some(member.group)
+            }
+            Err(_) => None,
+        };
+
+        Ok(Some(Nico {
+            nico_type: NicoType::Mint {
+                metadata: MetadataType::Token22Extension,
+                current_owner,
+                current_token_account,
+            },
+            pubkey,
+            group,
+            owner_program: spl_token_2022::ID,
+        }))
+    }
 }
@@ -0,0 +1,274 @@
+use mpl_core::accounts::BaseAssetV1;
+use mpl_token_metadata::{accounts::TokenRecord, types::TokenState};
+use nifty_asset::accounts::Asset;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, AccountState},
+};
+
+use crate::{
+    assertions::assert_program_owner, find_account, token_record_address, Error, MetadataType,
+    Nico, NicoType,
+};
+
+impl<'a: 'c, 'c> Nico<'a> {
+    /// Read the current owner of the asset from the asset account, the
+    /// compressed leaf or, for mints, the token account holding the asset.
+    /// The asset account must be in the remaining accounts, except for
+    /// compressed Nicos.
+    pub fn owner(
+        &'c self,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<Pubkey, ProgramError> {
+        match &self.nico_type {
+            NicoType::Nifty => {
+                let asset_info = find_account(&self.pubkey, remaining_accounts, "asset")?;
+                let asset = Asset::try_from(asset_info).map_err(|error| {
+                    msg!("Error: {}", error);
+                    Error::AssetDeserializationFailed
+                })?;
+                Ok(asset.owner)
+            }
+            NicoType::MxCore => {
+                let asset_info = find_account(&self.pubkey, remaining_accounts, "asset")?;
+                let asset = BaseAssetV1::try_from(asset_info).map_err(|error| {
+                    msg!("Error: {}", error);
+                    Error::AssetDeserializationFailed
+                })?;
+                Ok(asset.owner)
+            }
+            NicoType::Compressed { leaf, .. } => Ok(leaf.owner),
+            NicoType::Mint { .. } => Ok(self.holding_token_account(remaining_accounts)?.1.owner),
+        }
+    }
+
+    /// Check that `wallet` owns the asset. For mints the token account must
+    /// hold the asset and must not be frozen. pNFT token accounts are always
+    /// frozen by Token Metadata, so for those the token record, which must be
+    /// in the remaining accounts, must not be locked instead.
+    pub fn assert_owned_by(
+        &'c self,
+        wallet: &Pubkey,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> ProgramResult {
+        let owner = match &self.nico_type {
+            NicoType::Mint {
+                metadata,
+                current_token_account,
+                ..
+            } => {
+                let (token_account_info, token_account) =
+                    self.holding_token_account(remaining_accounts)?;
+
+                if token_account.state == AccountState::Frozen {
+                    let metadata = match metadata {
+                        MetadataType::Unknown => {
+                            let asset_info =
+                                find_account(&self.pubkey, remaining_accounts, "mint")?;
+                            MetadataType::infer(
+                                asset_info,
+                                *current_token_account,
+                                remaining_accounts,
+                            )?
+                        }
+                        x => *x,
+                    };
+                    if metadata != MetadataType::Mxprogrammable {
+                        msg!("Token account {} is frozen", token_account_info.key);
+                        return Err(Error::AssetFrozen.into());
+                    }
+                    self.assert_token_record_unlocked(token_account_info, remaining_accounts)?;
+                }
+
+                token_account.owner
+            }
+            _ => self.owner(remaining_accounts)?,
+        };
+
+        if owner != *wallet {
+            msg!(
+                "Nico {} is owned by {}, expected {}",
+                self.pubkey,
+                owner,
+                wallet
+            );
+            return Err(Error::NotOwnedBy.into());
+        }
+        Ok(())
+    }
+
+    // the current token account if one was given, otherwise the token
+    // account in the remaining accounts that holds the asset. either way it
+    // must belong to the token program of the mint and hold the asset
+    fn holding_token_account(
+        &'c self,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<(&'a AccountInfo<'a>, TokenAccount), ProgramError> {
+        let holds_asset = |x: &TokenAccount| x.mint == self.pubkey && x.amount == 1;
+
+        if let NicoType::Mint {
+            current_token_account: Some(token_account_info),
+            ..
+        } = &self.nico_type
+        {
+            assert_program_owner("token_account", token_account_info, &self.owner_program)?;
+
+            let data = token_account_info.try_borrow_data()?;
+            let token_account = StateWithExtensions::<TokenAccount>::unpack(&data)?.base;
+            if !holds_asset(&token_account) {
+                msg!(
+                    "Token account {} holds {} of mint {}, expected 1 of {}",
+                    token_account_info.key,
+                    token_account.amount,
+                    token_account.mint,
+                    self.pubkey
+                );
+                return Err(Error::InvalidTokenAccount.into());
+            }
+            return Ok((*token_account_info, token_account));
+        }
+
+        remaining_accounts
+            .iter()
+            .filter(|x| x.owner.eq(&self.owner_program))
+            .find_map(|x| {
+                let data = x.try_borrow_data().ok()?;
+                let token_account = StateWithExtensions::<TokenAccount>::unpack(&data)
+                    .ok()?
+                    .base;
+                holds_asset(&token_account).then_some((x, token_account))
+            })
+            .ok_or_else(|| {
+                msg!("No token account holding mint {} found", self.pubkey);
+                Error::MissingCurrentTokenAccount.into()
+            })
+    }
+
+    // a locked pNFT is frozen by its utility or locked transfer delegate
+    fn assert_token_record_unlocked(
+        &'c self,
+        token_account_info: &AccountInfo,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> ProgramResult {
+        let token_record_info = find_account(
+            &token_record_address(&self.pubkey, token_account_info.key),
+            remaining_accounts,
+            "token_record",
+        )?;
+        assert_program_owner("token_record", token_record_info, &mpl_token_metadata::ID)?;
+
+        let token_record = TokenRecord::safe_deserialize(&token_record_info.try_borrow_data()?)
+            .map_err(|error| {
+                msg!("Error: {}", error);
+                Error::DeserializationError
+            })?;
+        if token_record.state == TokenState::Locked {
+            msg!("Token record {} is locked", token_record_info.key);
+            return Err(Error::AssetFrozen.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_token_metadata::types::TokenStandard;
+
+    use crate::testing::{fake_metadata, fake_token_account, fake_token_record};
+
+    use super::*;
+
+    // a legacy NFT or pNFT with the given current token account
+    fn metaplex_nico(
+        token_standard: TokenStandard,
+        token_account: impl FnOnce(&Pubkey) -> &'static AccountInfo<'static>,
+    ) -> Nico<'static> {
+        let (mint_info, metadata_info) = fake_metadata(&spl_token::ID, Some(token_standard), None);
+
+        Nico::try_from_accounts(
+            mint_info,
+            Some(metadata_info),
+            None,
+            Some(token_account(mint_info.key)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn checks_owner_of_token_account() {
+        let owner = Pubkey::new_unique();
+        let nico = metaplex_nico(TokenStandard::NonFungible, |mint| {
+            fake_token_account(mint, &owner, &spl_token::ID, false)
+        });
+
+        assert_eq!(nico.owner(&[]), Ok(owner));
+        assert_eq!(nico.assert_owned_by(&owner, &[]), Ok(()));
+        assert_eq!(
+            nico.assert_owned_by(&Pubkey::new_unique(), &[]),
+            Err(Error::NotOwnedBy.into())
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_token_account() {
+        let owner = Pubkey::new_unique();
+
+        // holding another mint
+        let nico = metaplex_nico(TokenStandard::NonFungible, |_| {
+            fake_token_account(&Pubkey::new_unique(), &owner, &spl_token::ID, false)
+        });
+        assert_eq!(
+            nico.assert_owned_by(&owner, &[]),
+            Err(Error::InvalidTokenAccount.into())
+        );
+
+        // of another token program
+        let nico = metaplex_nico(TokenStandard::NonFungible, |mint| {
+            fake_token_account(mint, &owner, &spl_token_2022::ID, false)
+        });
+        assert_eq!(
+            nico.assert_owned_by(&owner, &[]),
+            Err(Error::InvalidProgramOwner.into())
+        );
+    }
+
+    #[test]
+    fn rejects_frozen_nft_and_locked_pnft() {
+        let owner = Pubkey::new_unique();
+
+        let nico = metaplex_nico(TokenStandard::NonFungible, |mint| {
+            fake_token_account(mint, &owner, &spl_token::ID, true)
+        });
+        assert_eq!(
+            nico.assert_owned_by(&owner, &[]),
+            Err(Error::AssetFrozen.into())
+        );
+
+        // pNFT token accounts are always frozen, the token record decides
+        for (state, expected) in [
+            (TokenState::Unlocked, Ok(())),
+            (TokenState::Locked, Err(Error::AssetFrozen.into())),
+        ] {
+            let nico = metaplex_nico(TokenStandard::ProgrammableNonFungible, |mint| {
+                fake_token_account(mint, &owner, &spl_token::ID, true)
+            });
+            let NicoType::Mint {
+                current_token_account: Some(token_account_info),
+                ..
+            } = nico.nico_type
+            else {
+                unreachable!()
+            };
+            let token_record_info = fake_token_record(&nico.pubkey, token_account_info.key, state);
+
+            assert_eq!(
+                nico.assert_owned_by(&owner, vec![token_record_info.clone()].leak()),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{find_account, Nico, NicoAccounts};
+
+/// Accounts and signer seeds common to every operation on a Nico. Burn and
+/// lock take them as is, transfer and delegate add their own accounts.
+pub struct NicoParams<'a, 'b> {
+    pub nico_pubkey: Pubkey,
+    pub nico_owner_program: Pubkey,
+    pub authority_info: Option<&'a AccountInfo<'a>>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub group_asset_opt_info: Option<&'a AccountInfo<'a>>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+    // accounts parsed against the NicoAccountLayout of the operation. empty
+    // unless set, in which case everything is searched for
+    pub accounts: NicoAccounts<'a>,
+}
+
+impl<'a: 'c, 'b, 'c> NicoParams<'a, 'b> {
+    pub fn new(
+        nico: &'c Nico<'a>,
+        payer_info: &'a AccountInfo<'a>,
+        authority_info: Option<&'a AccountInfo<'a>>,
+        signer_seeds: &'b [&'b [&'b [u8]]],
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<NicoParams<'a, 'b>, ProgramError> {
+        let group_asset_opt_info = nico
+            .group
+            .map(|x| find_account(&x, remaining_accounts, "group"))
+            .transpose()?;
+
+        Ok(NicoParams {
+            nico_pubkey: nico.pubkey,
+            nico_owner_program: nico.owner_program,
+            authority_info,
+            payer_info,
+            group_asset_opt_info,
+            signer_seeds,
+            accounts: NicoAccounts::default(),
+        })
+    }
+}
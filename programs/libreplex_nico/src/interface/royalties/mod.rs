@@ -0,0 +1,75 @@
+pub mod pay_royalties;
+pub mod royalties_core;
+pub mod royalties_metaplex;
+pub mod royalties_nifty;
+
+pub use pay_royalties::*;
+use royalties_core::core_royalty_enforcement;
+use royalties_metaplex::metaplex_royalty_enforcement;
+use royalties_nifty::nifty_royalty_enforcement;
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{Error, MetadataType, Nico, NicoCreator, NicoType};
+
+/// How a standard enforces royalties on transfer, if at all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NicoRoyaltyEnforcement {
+    // royalties are left to the marketplace
+    None,
+    // pNFT transfers are checked against this token auth rule set
+    RuleSet(Pubkey),
+    // core transfers may only be made by these programs
+    ProgramAllowList(Vec<Pubkey>),
+    // core transfers may not be made by these programs
+    ProgramDenyList(Vec<Pubkey>),
+    // nifty transfers must satisfy the constraint of the royalties extension
+    Constraint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NicoRoyaltyInfo {
+    pub basis_points: u16,
+    // shares add up to 100
+    pub creators: Vec<NicoCreator>,
+    pub enforcement: NicoRoyaltyEnforcement,
+}
+
+impl<'a: 'c, 'c> Nico<'a> {
+    /// Read the royalties of the asset, from [`Nico::metadata`] plus how the
+    /// standard enforces them, with the same remaining accounts. Token-2022
+    /// metadata has no royalties, so Token-2022 mints report none.
+    pub fn royalty_info(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<NicoRoyaltyInfo, ProgramError> {
+        let metadata = self.metadata(asset_info, remaining_accounts)?;
+
+        let enforcement = match &self.nico_type {
+            NicoType::Nifty => nifty_royalty_enforcement(asset_info)?,
+            NicoType::MxCore => {
+                core_royalty_enforcement(asset_info, self.group, remaining_accounts)?
+            }
+            NicoType::Compressed { .. } => {
+                msg!("Compressed Nicos only store a hash of their creators");
+                return Err(Error::UnsupportedAssetType.into());
+            }
+            NicoType::Mint { metadata, .. } => match metadata {
+                MetadataType::Token22Extension => NicoRoyaltyEnforcement::None,
+                MetadataType::Unknown
+                | MetadataType::MxNonProgrammable
+                | MetadataType::Mxprogrammable => {
+                    metaplex_royalty_enforcement(asset_info, remaining_accounts)?
+                }
+            },
+        };
+
+        Ok(NicoRoyaltyInfo {
+            basis_points: metadata.seller_fee_bps,
+            creators: metadata.creators,
+            enforcement,
+        })
+    }
+}
@@ -0,0 +1,284 @@
+use solana_program::{
+    account_info::AccountInfo, msg, program::invoke_signed, program_error::ProgramError,
+    system_instruction, system_program,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+
+use crate::{
+    assertions::assert_same_pubkeys, associated_token_address, find_account, Error, NicoRoyaltyInfo,
+};
+
+/// Currency the royalties are paid in.
+pub enum NicoRoyaltyPayment<'a> {
+    // creator accounts are the creator wallets
+    Lamports {
+        system_program_info: &'a AccountInfo<'a>,
+    },
+    // creator accounts are the associated token accounts of the creators
+    Token {
+        mint_info: &'a AccountInfo<'a>,
+        payer_token_account_info: &'a AccountInfo<'a>,
+        token_program_info: &'a AccountInfo<'a>,
+    },
+}
+
+impl NicoRoyaltyInfo {
+    /// Total royalty due on a sale, before it is split between the creators.
+    pub fn royalty_amount(&self, sale_price: u64) -> Result<u64, ProgramError> {
+        let amount = (sale_price as u128)
+            .checked_mul(self.basis_points as u128)
+            .ok_or(Error::NumericalOverflow)?
+            / 10_000;
+        Ok(u64::try_from(amount).map_err(|_| Error::NumericalOverflow)?)
+    }
+
+    /// Split the royalty on `sale_price` between the creators by their
+    /// shares. Each creator must have an account in `creator_accounts`.
+    /// Rounding dust stays with the payer. Returns the amount paid.
+    pub fn pay_royalties<'a>(
+        &self,
+        sale_price: u64,
+        payer: &'a AccountInfo<'a>,
+        creator_accounts: &'a [AccountInfo<'a>],
+        payment: NicoRoyaltyPayment<'a>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64, ProgramError> {
+        match &payment {
+            NicoRoyaltyPayment::Lamports {
+                system_program_info,
+            } => {
+                assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+            }
+            NicoRoyaltyPayment::Token {
+                token_program_info, ..
+            } => {
+                if token_program_info.key != &spl_token::ID
+                    && token_program_info.key != &spl_token_2022::ID
+                {
+                    msg!("Invalid token program {}", token_program_info.key);
+                    return Err(Error::InvalidTokenProgram.into());
+                }
+            }
+        }
+
+        let royalty_amount = self.royalty_amount(sale_price)?;
+        let mut paid: u64 = 0;
+
+        for creator in self.creators.iter() {
+            let amount = royalty_amount
+                .checked_mul(creator.share as u64)
+                .ok_or(Error::NumericalOverflow)?
+                / 100;
+            if amount == 0 {
+                continue;
+            }
+
+            match &payment {
+                NicoRoyaltyPayment::Lamports {
+                    system_program_info,
+                } => {
+                    let creator_info =
+                        find_account(&creator.address, creator_accounts, "creator")?;
+                    invoke_signed(
+                        &system_instruction::transfer(payer.key, creator_info.key, amount),
+                        &[
+                            payer.clone(),
+                            creator_info.clone(),
+                            (*system_program_info).clone(),
+                        ],
+                        signer_seeds,
+                    )?;
+                }
+                NicoRoyaltyPayment::Token {
+                    mint_info,
+                    payer_token_account_info,
+                    token_program_info,
+                } => {
+                    let creator_token_account_info = find_account(
+                        &associated_token_address(
+                            &creator.address,
+                            mint_info.key,
+                            token_program_info.key,
+                        ),
+                        creator_accounts,
+                        "creator_token_account",
+                    )?;
+                    let decimals = {
+                        let mint_data = mint_info.try_borrow_data()?;
+                        StateWithExtensions::<Mint>::unpack(&mint_data)?.base.decimals
+                    };
+                    invoke_signed(
+                        &spl_token_2022::instruction::transfer_checked(
+                            token_program_info.key,
+                            payer_token_account_info.key,
+                            mint_info.key,
+                            creator_token_account_info.key,
+                            payer.key,
+                            &[],
+                            amount,
+                            decimals,
+                        )?,
+                        &[
+                            (*payer_token_account_info).clone(),
+                            (*mint_info).clone(),
+                            creator_token_account_info.clone(),
+                            payer.clone(),
+                        ],
+                        signer_seeds,
+                    )?;
+                }
+            }
+
+            paid = paid.checked_add(amount).ok_or(Error::NumericalOverflow)?;
+        }
+
+        Ok(paid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{
+        instruction::Instruction, program_utils::limited_deserialize, pubkey::Pubkey,
+        system_instruction::SystemInstruction,
+    };
+    use spl_token_2022::instruction::TokenInstruction;
+
+    use crate::{
+        testing::{fake_account, fake_mint, fake_program, fake_wallet, record_invokes},
+        NicoCreator, NicoRoyaltyEnforcement,
+    };
+
+    use super::*;
+
+    // 5% split 33 / 33 / 34, and a creator with no share
+    fn royalty_info() -> NicoRoyaltyInfo {
+        NicoRoyaltyInfo {
+            basis_points: 500,
+            creators: [33, 33, 34, 0]
+                .into_iter()
+                .map(|share| NicoCreator {
+                    address: Pubkey::new_unique(),
+                    verified: true,
+                    share,
+                })
+                .collect(),
+            enforcement: NicoRoyaltyEnforcement::None,
+        }
+    }
+
+    fn lamport_transfer(instruction: &Instruction) -> (Pubkey, u64) {
+        assert_eq!(instruction.program_id, system_program::ID);
+        match limited_deserialize(&instruction.data, 1024) {
+            Ok(SystemInstruction::Transfer { lamports }) => {
+                (instruction.accounts[1].pubkey, lamports)
+            }
+            x => panic!("unexpected instruction {:?}", x),
+        }
+    }
+
+    #[test]
+    fn splits_lamports_by_share_leaving_dust_with_payer() {
+        let royalty_info = royalty_info();
+        let creator_accounts: Vec<_> = royalty_info
+            .creators
+            .iter()
+            .map(|x| fake_account(x.address, system_program::ID, vec![]).clone())
+            .collect();
+
+        // a royalty of 1001, which does not split evenly
+        let (result, invokes) = record_invokes(|| {
+            royalty_info.pay_royalties(
+                20_020,
+                fake_wallet(),
+                creator_accounts.leak(),
+                NicoRoyaltyPayment::Lamports {
+                    system_program_info: fake_program(&system_program::ID),
+                },
+                &[],
+            )
+        });
+
+        assert_eq!(result, Ok(1000));
+        let transfers: Vec<_> = invokes.iter().map(lamport_transfer).collect();
+        assert_eq!(
+            transfers,
+            [
+                (royalty_info.creators[0].address, 330),
+                (royalty_info.creators[1].address, 330),
+                (royalty_info.creators[2].address, 340),
+            ]
+        );
+    }
+
+    #[test]
+    fn pays_creator_token_accounts() {
+        let royalty_info = royalty_info();
+        let mint_info = fake_mint(&spl_token::ID);
+        let creator_token_accounts: Vec<_> = royalty_info
+            .creators
+            .iter()
+            .map(|x| associated_token_address(&x.address, mint_info.key, &spl_token::ID))
+            .collect();
+        let creator_accounts: Vec<_> = creator_token_accounts
+            .iter()
+            .map(|x| fake_account(*x, spl_token::ID, vec![]).clone())
+            .collect();
+
+        let (result, invokes) = record_invokes(|| {
+            royalty_info.pay_royalties(
+                1_000_000,
+                fake_wallet(),
+                creator_accounts.leak(),
+                NicoRoyaltyPayment::Token {
+                    mint_info,
+                    payer_token_account_info: fake_account(
+                        Pubkey::new_unique(),
+                        spl_token::ID,
+                        vec![],
+                    ),
+                    token_program_info: fake_program(&spl_token::ID),
+                },
+                &[],
+            )
+        });
+
+        assert_eq!(result, Ok(50_000));
+        let transfers: Vec<_> = invokes
+            .iter()
+            .map(|x| match TokenInstruction::unpack(&x.data) {
+                Ok(TokenInstruction::TransferChecked { amount, decimals }) => {
+                    assert_eq!(decimals, 0);
+                    (x.accounts[2].pubkey, amount)
+                }
+                _ => panic!("unexpected instruction"),
+            })
+            .collect();
+        assert_eq!(
+            transfers,
+            [
+                (creator_token_accounts[0], 16_500),
+                (creator_token_accounts[1], 16_500),
+                (creator_token_accounts[2], 17_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_creator_accounts() {
+        let (result, invokes) = record_invokes(|| {
+            royalty_info().pay_royalties(
+                20_020,
+                fake_wallet(),
+                &[],
+                NicoRoyaltyPayment::Lamports {
+                    system_program_info: fake_program(&system_program::ID),
+                },
+                &[],
+            )
+        });
+
+        assert_eq!(result, Err(Error::MissingRemainingAccount.into()));
+        assert!(invokes.is_empty());
+    }
+}
@@ -0,0 +1,18 @@
+use mpl_core::types::RuleSet;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{metadata_core::core_royalties, NicoRoyaltyEnforcement};
+
+pub fn core_royalty_enforcement<'a>(
+    asset_info: &'a AccountInfo<'a>,
+    collection: Option<Pubkey>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<NicoRoyaltyEnforcement, ProgramError> {
+    Ok(
+        match core_royalties(asset_info, collection, remaining_accounts)?.map(|x| x.rule_set) {
+            Some(RuleSet::ProgramAllowList(x)) => NicoRoyaltyEnforcement::ProgramAllowList(x),
+            Some(RuleSet::ProgramDenyList(x)) => NicoRoyaltyEnforcement::ProgramDenyList(x),
+            Some(RuleSet::None) | None => NicoRoyaltyEnforcement::None,
+        },
+    )
+}
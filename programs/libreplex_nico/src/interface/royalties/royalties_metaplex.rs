@@ -0,0 +1,19 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::{find_account, metadata_address, read_rule_set, NicoRoyaltyEnforcement};
+
+pub fn metaplex_royalty_enforcement<'a>(
+    mint_info: &'a AccountInfo<'a>,
+    remaining_accounts: &'a [AccountInfo<'a>],
+) -> Result<NicoRoyaltyEnforcement, ProgramError> {
+    let metadata_info = find_account(
+        &metadata_address(mint_info.key),
+        remaining_accounts,
+        "metadata",
+    )?;
+
+    Ok(match read_rule_set(&metadata_info.try_borrow_data()?)? {
+        Some(rule_set) => NicoRoyaltyEnforcement::RuleSet(rule_set),
+        None => NicoRoyaltyEnforcement::None,
+    })
+}
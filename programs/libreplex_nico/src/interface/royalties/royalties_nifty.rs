@@ -0,0 +1,19 @@
+use nifty_asset::{constraints::OperatorType, extensions::Royalties, state::Asset};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::NicoRoyaltyEnforcement;
+
+pub fn nifty_royalty_enforcement(
+    asset_info: &AccountInfo,
+) -> Result<NicoRoyaltyEnforcement, ProgramError> {
+    let data = asset_info.try_borrow_data()?;
+
+    let enforced = Asset::get::<Royalties>(&data)
+        .is_some_and(|x| x.constraint.operator.operator_type() != OperatorType::Empty);
+
+    Ok(if enforced {
+        NicoRoyaltyEnforcement::Constraint
+    } else {
+        NicoRoyaltyEnforcement::None
+    })
+}
@@ -1,23 +1,29 @@
+pub mod transfer_compressed;
 pub mod transfer_core;
+pub mod transfer_legacy;
+pub mod transfer_many;
 pub mod transfer_nifty;
 pub mod transfer_pnft;
 
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use transfer_compressed::{check_and_transfer_compressed, TransferCompressedParams};
 use transfer_core::{check_and_transfer_core, TransferCoreParams};
+use transfer_legacy::{check_and_transfer_legacy, TransferLegacyParams};
 use transfer_nifty::{check_and_transfer_nifty, TransferNiftyParams};
 use transfer_pnft::{check_and_transfer_pnft, TransferPnftParams};
 
-use crate::{find_account_or_panic, Nico, NicoType};
+pub use transfer_many::*;
+
+use crate::{Error, MetadataType, Nico, NicoParams, NicoType};
 
 pub struct NicoTransferParams<'a, 'b> {
-    pub nico_pubkey: Pubkey,
-    pub nico_owner_program: Pubkey,
-    pub authority_info: Option<&'a AccountInfo<'a>>,
-    pub payer_info: &'a AccountInfo<'a>,
-    // pub asset_info: &'a AccountInfo<'a>,
+    pub base: NicoParams<'a, 'b>,
     pub recipient_info: &'a AccountInfo<'a>,
-    pub group_asset_opt_info: Option<&'a AccountInfo<'a>>,
-    pub signer_seeds: &'b [&'b [&'b [u8]]],
+    // program accounts resolved once for a batch. empty for single transfers
+    pub shared_accounts: NicoSharedAccounts<'a>,
 }
 
 impl<'a: 'c, 'b, 'c> NicoTransferParams<'a, 'b> {
@@ -28,25 +34,47 @@ impl<'a: 'c, 'b, 'c> NicoTransferParams<'a, 'b> {
         authority_info: Option<&'a AccountInfo<'a>>,
         signer_seeds: &'b [&'b [&'b [u8]]],
         remaining_accounts: &'a [AccountInfo<'a>],
-    ) -> NicoTransferParams<'a, 'b> {
-        let group_asset_opt_info = nico
-            .group
-            .map(|x| find_account_or_panic(&x, remaining_accounts, "group"));
-
-        NicoTransferParams {
-            // asset_info: &nico.account_info,
-            nico_owner_program: nico.owner_program,
-            nico_pubkey: nico.pubkey,
-            authority_info,
+    ) -> Result<NicoTransferParams<'a, 'b>, ProgramError> {
+        Ok(NicoTransferParams {
+            base: NicoParams::new(
+                nico,
+                payer_info,
+                authority_info,
+                signer_seeds,
+                remaining_accounts,
+            )?,
             recipient_info,
-            group_asset_opt_info,
-            payer_info,
-            signer_seeds,
+            shared_accounts: NicoSharedAccounts::default(),
+        })
+    }
+
+    /// Find an account in the batch accounts, then in the parsed accounts,
+    /// then in the remaining accounts.
+    pub fn find(
+        &self,
+        account_id: &Pubkey,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        name: &str,
+    ) -> Result<&'a AccountInfo<'a>, ProgramError> {
+        match self.shared_accounts.get(account_id) {
+            Some(x) => Ok(x),
+            None => self.base.accounts.find(account_id, remaining_accounts, name),
         }
     }
 }
 
+/// The transfer CPI accounts of each standard, see [`Nico::transfer_params`].
+pub enum NicoStandardTransferParams<'a, 'b> {
+    Nifty(TransferNiftyParams<'a, 'b>),
+    Core(TransferCoreParams<'a, 'b>),
+    Compressed(TransferCompressedParams<'a, 'b>),
+    Legacy(TransferLegacyParams<'a, 'b>),
+    Pnft(TransferPnftParams<'a, 'b>),
+}
+
 impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Transfer the asset to the target wallet. For compressed Nicos the
+    /// merkle tree held by the Nico is used and `asset_info` is ignored.
     pub fn transfer(
         &'c self,
         asset_info: &'a AccountInfo<'a>,
@@ -63,48 +91,101 @@ impl<'a: 'c, 'b, 'c> Nico<'a> {
             authority,
             signer_seeds,
             remaining_accounts,
-        );
+        )?;
+
+        self.transfer_with_params(asset_info, params, remaining_accounts)
+    }
+
+    /// Same as [`Nico::transfer`], with the params built by the caller, e.g.
+    /// to set the accounts parsed by [`crate::NicoAccountLayout::parse`].
+    pub fn transfer_with_params(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        params: NicoTransferParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> ProgramResult {
+        match self.transfer_params(asset_info, &params, remaining_accounts)? {
+            NicoStandardTransferParams::Nifty(x) => check_and_transfer_nifty(x),
+            NicoStandardTransferParams::Core(x) => check_and_transfer_core(x),
+            NicoStandardTransferParams::Compressed(x) => check_and_transfer_compressed(x),
+            NicoStandardTransferParams::Legacy(x) => check_and_transfer_legacy(x),
+            NicoStandardTransferParams::Pnft(x) => check_and_transfer_pnft(x),
+        }
+    }
 
+    /// Resolve the accounts of the transfer CPI for the standard of this
+    /// Nico, without invoking it.
+    pub fn transfer_params(
+        &'c self,
+        asset_info: &'a AccountInfo<'a>,
+        params: &NicoTransferParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<NicoStandardTransferParams<'a, 'b>, ProgramError> {
         match &self.nico_type {
-            NicoType::Nifty => {
-                let nifty_params = TransferNiftyParams::from_nico_transfer_params(
+            NicoType::Nifty => Ok(NicoStandardTransferParams::Nifty(
+                TransferNiftyParams::from_nico_transfer_params(
                     asset_info,
-                    &params,
+                    params,
                     remaining_accounts,
-                );
-                check_and_transfer_nifty(nifty_params)
-            }
-            NicoType::MxCore => {
-                let core_params = TransferCoreParams::from_nico_transfer_params(
+                )?,
+            )),
+            NicoType::MxCore => Ok(NicoStandardTransferParams::Core(
+                TransferCoreParams::from_nico_transfer_params(
                     asset_info,
-                    &params,
+                    params,
                     remaining_accounts,
-                );
-                check_and_transfer_core(core_params)
-            }
+                )?,
+            )),
+            NicoType::Compressed {
+                merkle_tree,
+                tree_config,
+                proof,
+                leaf,
+            } => Ok(NicoStandardTransferParams::Compressed(
+                TransferCompressedParams::from_nico_transfer_params(
+                    merkle_tree,
+                    tree_config,
+                    proof,
+                    *leaf,
+                    params,
+                    remaining_accounts,
+                )?,
+            )),
             NicoType::Mint {
                 metadata,
                 current_owner,
                 current_token_account,
-            } => match &metadata {
-                crate::MetadataType::Unknown => todo!(),
-                crate::MetadataType::Token22Extension => {
-                    panic!("Nico type MxNonProgrammable not supported yet");
+            } => {
+                let metadata = match metadata {
+                    // token standard not set on the metadata. work it out from the accounts
+                    MetadataType::Unknown => {
+                        MetadataType::infer(asset_info, *current_token_account, remaining_accounts)?
+                    }
+                    x => *x,
+                };
+                match metadata {
+                    MetadataType::Unknown => Err(Error::InsufficientAccountsToClassify.into()),
+                    MetadataType::Token22Extension | MetadataType::MxNonProgrammable => {
+                        Ok(NicoStandardTransferParams::Legacy(
+                            TransferLegacyParams::from_nico_transfer_params(
+                                asset_info,
+                                current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                                params,
+                                remaining_accounts,
+                            )?,
+                        ))
+                    }
+                    MetadataType::Mxprogrammable => Ok(NicoStandardTransferParams::Pnft(
+                        TransferPnftParams::from_nico_transfer_params(
+                            asset_info,
+                            current_owner.ok_or(Error::MissingCurrentOwner)?,
+                            current_token_account.ok_or(Error::MissingCurrentTokenAccount)?,
+                            params,
+                            remaining_accounts,
+                        )?,
+                    )),
                 }
-                crate::MetadataType::MxNonProgrammable => {
-                    panic!("Nico type MxNonProgrammable not supported yet");
-                }
-                crate::MetadataType::Mxprogrammable => {
-                    let programmable_mx_params = TransferPnftParams::from_nico_transfer_params(
-                        asset_info,
-                        current_owner.unwrap_or_else(||panic!("This Nico was constructed without current owner. Cannot transfer")),
-                        current_token_account.unwrap_or_else(||panic!("This Nico was constructed without current token account. Cannot transfer")),
-                        &params,
-                        remaining_accounts,
-                    );
-                    check_and_transfer_pnft(programmable_mx_params)
-                }
-            },
+            }
         }
     }
 }
@@ -0,0 +1,160 @@
+use mpl_bubblegum::{
+    instructions::{TransferCpi as BubblegumTransferCpi, TransferInstructionArgs},
+    programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    system_program,
+};
+
+use crate::{assertions::assert_same_pubkeys, CompressedLeaf, Error, NicoTransferParams};
+
+pub struct TransferCompressedParams<'a, 'b> {
+    pub bubblegum_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub merkle_tree_info: &'a AccountInfo<'a>,
+    pub tree_config_info: &'a AccountInfo<'a>,
+    pub new_owner_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    leaf_owner_info: &'a AccountInfo<'a>,
+    leaf_delegate_info: &'a AccountInfo<'a>,
+    log_wrapper_info: &'a AccountInfo<'a>,
+    compression_program_info: &'a AccountInfo<'a>,
+    proof: &'a [AccountInfo<'a>],
+    leaf: CompressedLeaf,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> TransferCompressedParams<'a, 'b> {
+    pub fn from_nico_transfer_params(
+        merkle_tree_info: &'a AccountInfo<'a>,
+        tree_config_info: &'a AccountInfo<'a>,
+        proof: &'a [AccountInfo<'a>],
+        leaf: CompressedLeaf,
+        params: &NicoTransferParams<'a, 'b>,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<TransferCompressedParams<'a, 'b>, ProgramError> {
+        let system_program_info = params.find(
+            &system_program::ID,
+            remaining_accounts,
+            "system_program",
+        )?;
+
+        let bubblegum_program_info = params.find(
+            &mpl_bubblegum::ID,
+            remaining_accounts,
+            "bubblegum_program",
+        )?;
+
+        let log_wrapper_info = params.find(
+            &SPL_NOOP_ID,
+            remaining_accounts,
+            "log_wrapper",
+        )?;
+
+        let compression_program_info = params.find(
+            &SPL_ACCOUNT_COMPRESSION_ID,
+            remaining_accounts,
+            "compression_program",
+        )?;
+
+        let authority_info = params.base.authority_info.map_or(params.base.payer_info, |x| x);
+
+        // the authority is usually the leaf owner or delegate, so it need not
+        // be repeated in the remaining accounts
+        let leaf_owner_info = if authority_info.key.eq(&leaf.owner) {
+            authority_info
+        } else {
+            params.base.accounts.find(&leaf.owner, remaining_accounts, "leaf_owner")?
+        };
+
+        let leaf_delegate_info = if authority_info.key.eq(&leaf.delegate) {
+            authority_info
+        } else if leaf_owner_info.key.eq(&leaf.delegate) {
+            leaf_owner_info
+        } else {
+            params.base.accounts.find(&leaf.delegate, remaining_accounts, "leaf_delegate")?
+        };
+
+        Ok(TransferCompressedParams {
+            bubblegum_program_info,
+            authority_info,
+            merkle_tree_info,
+            tree_config_info,
+            new_owner_info: params.recipient_info,
+            system_program_info,
+            leaf_owner_info,
+            leaf_delegate_info,
+            log_wrapper_info,
+            compression_program_info,
+            proof,
+            leaf,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_transfer_compressed(params: TransferCompressedParams<'_, '_>) -> ProgramResult {
+    let TransferCompressedParams {
+        bubblegum_program_info,
+        authority_info,
+        merkle_tree_info,
+        tree_config_info,
+        new_owner_info,
+        system_program_info,
+        leaf_owner_info,
+        leaf_delegate_info,
+        log_wrapper_info,
+        compression_program_info,
+        proof,
+        leaf,
+        signer_seeds,
+    } = params;
+
+    assert_same_pubkeys(
+        "bubblegum_program",
+        bubblegum_program_info,
+        &mpl_bubblegum::ID,
+    )?;
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    // Bubblegum accepts a signature from either the leaf owner or the leaf delegate.
+    let owner_signs = authority_info.key.eq(leaf_owner_info.key);
+    let delegate_signs = authority_info.key.eq(leaf_delegate_info.key);
+    if !owner_signs && !delegate_signs {
+        msg!(
+            "Authority [{}] is neither the owner nor the delegate of leaf {}",
+            authority_info.key,
+            leaf.nonce
+        );
+        return Err(Error::InvalidTransferAuthority.into());
+    }
+
+    let proof_accounts = proof
+        .iter()
+        .map(|x| (x, false, false))
+        .collect::<Vec<_>>();
+
+    BubblegumTransferCpi {
+        __program: bubblegum_program_info,
+        tree_config: tree_config_info,
+        leaf_owner: (leaf_owner_info, owner_signs),
+        leaf_delegate: (leaf_delegate_info, delegate_signs),
+        new_leaf_owner: new_owner_info,
+        merkle_tree: merkle_tree_info,
+        log_wrapper: log_wrapper_info,
+        compression_program: compression_program_info,
+        system_program: system_program_info,
+        __args: TransferInstructionArgs {
+            root: leaf.root,
+            data_hash: leaf.data_hash,
+            creator_hash: leaf.creator_hash,
+            nonce: leaf.nonce,
+            index: leaf.index,
+        },
+    }
+    .invoke_signed_with_remaining_accounts(signer_seeds, &proof_accounts)?;
+
+    Ok(())
+}
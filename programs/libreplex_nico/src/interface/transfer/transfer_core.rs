@@ -1,7 +1,10 @@
 use mpl_core::instructions::{TransferV1Cpi as MplCoreTransferCpi, TransferV1InstructionArgs};
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, system_program};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    system_program,
+};
 
-use crate::{assertions::assert_same_pubkeys, find_account_or_panic, find_in_remaining_accounts, NicoTransferParams};
+use crate::{assertions::assert_same_pubkeys, NicoTransferParams};
 pub struct TransferCoreParams<'a, 'b> {
     pub mpl_core_program_info: &'a AccountInfo<'a>,
     pub authority_info: Option<&'a AccountInfo<'a>>,
@@ -13,29 +16,29 @@ pub struct TransferCoreParams<'a, 'b> {
     pub signer_seeds: &'b [&'b [&'b [u8]]],
 }
 
-impl<'a, 'b, 'c> TransferCoreParams<'a, 'b> {
+impl<'a, 'b> TransferCoreParams<'a, 'b> {
     pub fn from_nico_transfer_params(
         asset_info: &'a AccountInfo<'a>,
         params: &NicoTransferParams<'a, 'b>,
         remaining_accounts: &'a [AccountInfo<'a>],
-    ) -> TransferCoreParams<'a, 'b> {
+    ) -> Result<TransferCoreParams<'a, 'b>, ProgramError> {
         // need to derive extra system account
         let system_program_info =
-        find_account_or_panic(&system_program::ID, remaining_accounts, "system_program");
+            params.find(&system_program::ID, remaining_accounts, "system_program")?;
 
         let mpl_core_program_info =
-        find_account_or_panic(&mpl_core::ID, remaining_accounts, "mpl_core_program");
-      
-        TransferCoreParams {
+            params.find(&mpl_core::ID, remaining_accounts, "mpl_core_program")?;
+
+        Ok(TransferCoreParams {
             mpl_core_program_info,
-            authority_info: params.authority_info,
+            authority_info: params.base.authority_info,
             asset_info,
             new_owner_info: params.recipient_info,
-            collection_asset_opt_info: params.group_asset_opt_info,
-            signer_seeds: params.signer_seeds,
-            payer_info: params.payer_info,
+            collection_asset_opt_info: params.base.group_asset_opt_info,
+            signer_seeds: params.base.signer_seeds,
+            payer_info: params.base.payer_info,
             system_program_info,
-        }
+        })
     }
 }
 
@@ -60,12 +63,11 @@ pub fn check_and_transfer_core(params: TransferCoreParams<'_, '_>) -> ProgramRes
 
     assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
 
-    let data = asset_info.try_borrow_data().unwrap();
+    let data = asset_info.try_borrow_data()?;
 
     // Drop the data reference before the CPI.
     drop(data);
 
-    println!("transfer core");
     MplCoreTransferCpi {
         __program: mpl_core_program_info,
         asset: asset_info,
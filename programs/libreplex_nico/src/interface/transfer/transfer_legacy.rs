@@ -0,0 +1,168 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke_signed,
+    program_error::ProgramError, program_option::COption, system_program,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Mint},
+};
+
+use crate::{assertions::assert_same_pubkeys, associated_token_address, Error, NicoTransferParams};
+
+pub struct TransferLegacyParams<'a, 'b> {
+    pub spl_token_program_info: &'a AccountInfo<'a>,
+    pub authority_info: &'a AccountInfo<'a>,
+    pub asset_info: &'a AccountInfo<'a>,
+    pub new_owner_info: &'a AccountInfo<'a>,
+    pub payer_info: &'a AccountInfo<'a>,
+    pub system_program_info: &'a AccountInfo<'a>,
+    source_token_account_info: &'a AccountInfo<'a>,
+    target_token_account_info: &'a AccountInfo<'a>,
+    spl_ata_program: &'a AccountInfo<'a>,
+    pub signer_seeds: &'b [&'b [&'b [u8]]],
+}
+
+impl<'a, 'b> TransferLegacyParams<'a, 'b> {
+    pub fn from_nico_transfer_params(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoTransferParams<'a, 'b>,
+        // need source token account as it's the only one
+        // that cannot be derived if it's not an ATA
+        // target token accounts are forced to use ATA
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<TransferLegacyParams<'a, 'b>, ProgramError> {
+        let system_program_info = params.find(
+            &system_program::ID,
+            remaining_accounts,
+            "system_program",
+        )?;
+
+        let token_program = params.find(
+            &params.base.nico_owner_program,
+            remaining_accounts,
+            "token_program",
+        )?;
+        let spl_ata_program = params.find(
+            &spl_associated_token_account::ID,
+            remaining_accounts,
+            "associated_token_program",
+        )?;
+
+        let target_token_account_info = params.base.accounts.find(
+            &associated_token_address(
+                params.recipient_info.key,
+                &params.base.nico_pubkey,
+                &params.base.nico_owner_program,
+            ),
+            remaining_accounts,
+            "target_ata",
+        )?;
+
+        Ok(TransferLegacyParams {
+            spl_token_program_info: token_program,
+            // the authority can be either the owner or the delegate of the source token account
+            authority_info: params.base.authority_info.map_or(params.base.payer_info, |x| x),
+            asset_info: nico_asset_info,
+            new_owner_info: params.recipient_info,
+            payer_info: params.base.payer_info,
+            system_program_info,
+            source_token_account_info: current_token_account,
+            target_token_account_info,
+            spl_ata_program,
+            signer_seeds: params.base.signer_seeds,
+        })
+    }
+}
+
+pub fn check_and_transfer_legacy(params: TransferLegacyParams<'_, '_>) -> ProgramResult {
+    let TransferLegacyParams {
+        spl_token_program_info,
+        authority_info,
+        asset_info,
+        new_owner_info,
+        payer_info,
+        system_program_info,
+        source_token_account_info,
+        target_token_account_info,
+        spl_ata_program,
+        signer_seeds,
+    } = params;
+
+    if !spl_token_program_info.key.eq(&spl_token::ID)
+        && !spl_token_program_info.key.eq(&spl_token_2022::ID)
+    {
+        return Err(Error::InvalidTokenProgram.into());
+    }
+
+    assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
+
+    let mint_data = asset_info.try_borrow_data()?;
+    let decimals = StateWithExtensions::<Mint>::unpack(&mint_data)?.base.decimals;
+
+    // Drop the data reference before the CPI.
+    drop(mint_data);
+
+    let source_data = source_token_account_info.try_borrow_data()?;
+    let source_token_account = StateWithExtensions::<TokenAccount>::unpack(&source_data)?.base;
+
+    // Drop the data reference before the CPI.
+    drop(source_data);
+
+    // The authority must either own the source token account or be its delegate.
+    if source_token_account.owner != *authority_info.key
+        && (source_token_account.delegate != COption::Some(*authority_info.key)
+            || source_token_account.delegated_amount < 1)
+    {
+        msg!(
+            "Authority [{}] is neither the owner nor the delegate of token account [{}]",
+            authority_info.key,
+            source_token_account_info.key
+        );
+        return Err(Error::InvalidTransferAuthority.into());
+    }
+
+    if target_token_account_info.data_is_empty() {
+        invoke_signed(
+            &create_associated_token_account_idempotent(
+                payer_info.key,
+                new_owner_info.key,
+                asset_info.key,
+                spl_token_program_info.key,
+            ),
+            &[
+                payer_info.clone(),
+                target_token_account_info.clone(),
+                new_owner_info.clone(),
+                asset_info.clone(),
+                system_program_info.clone(),
+                spl_token_program_info.clone(),
+                spl_ata_program.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            spl_token_program_info.key,
+            source_token_account_info.key,
+            asset_info.key,
+            target_token_account_info.key,
+            authority_info.key,
+            &[],
+            1,
+            decimals,
+        )?,
+        &[
+            source_token_account_info.clone(),
+            asset_info.clone(),
+            target_token_account_info.clone(),
+            authority_info.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
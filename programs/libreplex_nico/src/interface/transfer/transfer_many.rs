@@ -0,0 +1,287 @@
+use libreplex_shared::sysvar_instructions_program;
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey, system_program,
+};
+
+use crate::{find_account, Error, Nico, NicoTransferParams, NicoType, AUTH_RULES_PROGRAM_ID};
+
+const SHARED_ACCOUNT_COUNT: usize = 12;
+
+/// Program accounts needed by every transfer of a standard, looked up once
+/// per batch instead of once per asset.
+#[derive(Clone, Copy, Default)]
+pub struct NicoSharedAccounts<'a> {
+    accounts: [Option<&'a AccountInfo<'a>>; SHARED_ACCOUNT_COUNT],
+}
+
+impl<'a> NicoSharedAccounts<'a> {
+    pub fn resolve(remaining_accounts: &'a [AccountInfo<'a>]) -> NicoSharedAccounts<'a> {
+        let program_ids: [Pubkey; SHARED_ACCOUNT_COUNT] = [
+            system_program::ID,
+            nifty_asset::ID,
+            mpl_core::ID,
+            mpl_bubblegum::ID,
+            SPL_NOOP_ID,
+            SPL_ACCOUNT_COMPRESSION_ID,
+            spl_token::ID,
+            spl_token_2022::ID,
+            spl_associated_token_account::ID,
+            mpl_token_metadata::ID,
+            sysvar_instructions_program::ID,
+            AUTH_RULES_PROGRAM_ID,
+        ];
+
+        NicoSharedAccounts {
+            accounts: program_ids
+                .map(|program_id| remaining_accounts.iter().find(|x| x.key.eq(&program_id))),
+        }
+    }
+
+    /// The resolved account with the given key, if any.
+    pub fn get(&self, account_id: &Pubkey) -> Option<&'a AccountInfo<'a>> {
+        self.accounts
+            .iter()
+            .flatten()
+            .find(|x| x.key.eq(account_id))
+            .copied()
+    }
+
+    /// Same as [`find_account`], but checks the resolved accounts first.
+    pub fn find(
+        &self,
+        account_id: &Pubkey,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        name: &str,
+    ) -> Result<&'a AccountInfo<'a>, ProgramError> {
+        match self.get(account_id) {
+            Some(x) => Ok(x),
+            None => find_account(account_id, remaining_accounts, name),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NicoBatchMode {
+    // stop at the first failure and fail the instruction
+    Atomic,
+    // carry on after a failure and report it in the results. a failing
+    // CPI still aborts the transaction, so this only covers failures
+    // found before the CPI (missing accounts, unsupported assets, ...)
+    BestEffort,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NicoTransferResult {
+    pub nico: Pubkey,
+    pub result: ProgramResult,
+}
+
+// transfers of the same standard run next to each other
+fn standard_order(nico_type: &NicoType) -> u8 {
+    match nico_type {
+        NicoType::Nifty => 0,
+        NicoType::MxCore => 1,
+        NicoType::Compressed { .. } => 2,
+        NicoType::Mint { .. } => 3,
+    }
+}
+
+impl<'a: 'c, 'b, 'c> Nico<'a> {
+    /// Transfer several Nicos, of any mix of standards, in one call.
+    /// `recipients` holds either one wallet per Nico or a single wallet for
+    /// all of them. The assets are found in the remaining accounts by
+    /// pubkey, except compressed ones which use the merkle tree they hold.
+    /// Results come back in the order of `nicos`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_many(
+        nicos: &'c [Nico<'a>],
+        payer: &'a AccountInfo<'a>,
+        recipients: &[&'a AccountInfo<'a>],
+        authority: Option<&'a AccountInfo<'a>>,
+        mode: NicoBatchMode,
+        remaining_accounts: &'a [AccountInfo<'a>],
+        signer_seeds: &'b [&'b [&'b [u8]]],
+    ) -> Result<Vec<NicoTransferResult>, ProgramError> {
+        if recipients.len() != 1 && recipients.len() != nicos.len() {
+            msg!(
+                "{} recipients provided for {} assets",
+                recipients.len(),
+                nicos.len()
+            );
+            return Err(Error::RecipientCountMismatch.into());
+        }
+
+        let shared_accounts = NicoSharedAccounts::resolve(remaining_accounts);
+
+        let mut order: Vec<usize> = (0..nicos.len()).collect();
+        order.sort_by_key(|&i| standard_order(&nicos[i].nico_type));
+
+        let mut results: Vec<NicoTransferResult> = nicos
+            .iter()
+            .map(|nico| NicoTransferResult {
+                nico: nico.pubkey,
+                result: Ok(()),
+            })
+            .collect();
+
+        for i in order {
+            let nico = &nicos[i];
+            let recipient = recipients[if recipients.len() == 1 { 0 } else { i }];
+
+            let result = NicoTransferParams::new(
+                nico,
+                payer,
+                recipient,
+                authority,
+                signer_seeds,
+                remaining_accounts,
+            )
+            .and_then(|mut params| {
+                params.shared_accounts = shared_accounts;
+                let asset_info = match &nico.nico_type {
+                    NicoType::Compressed { merkle_tree, .. } => merkle_tree,
+                    _ => find_account(&nico.pubkey, remaining_accounts, "asset")?,
+                };
+                nico.transfer_with_params(asset_info, params, remaining_accounts)
+            });
+
+            if let Err(error) = &result {
+                msg!("Transfer of {} failed: {}", nico.pubkey, error);
+                if mode == NicoBatchMode::Atomic {
+                    return Err(error.clone());
+                }
+            }
+            results[i].result = result;
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mpl_core::types::UpdateAuthority;
+
+    use crate::testing::{
+        fake_core_asset, fake_nifty_asset, fake_program, fake_wallet, record_invokes,
+    };
+
+    use super::*;
+
+    // core and nifty assets, interleaved, and the accounts to transfer them.
+    // the asset at `missing`, if any, is left out of the remaining accounts
+    fn batch(missing: Option<usize>) -> (Vec<Nico<'static>>, &'static [AccountInfo<'static>]) {
+        let asset_infos = [
+            fake_core_asset(UpdateAuthority::None),
+            fake_nifty_asset(&Pubkey::new_unique(), None),
+            fake_core_asset(UpdateAuthority::None),
+            fake_nifty_asset(&Pubkey::new_unique(), None),
+        ];
+        let nicos = asset_infos
+            .iter()
+            .map(|x| Nico::try_from_accounts(x, None, None, None).unwrap())
+            .collect();
+
+        let mut remaining_accounts = vec![
+            fake_program(&system_program::ID).clone(),
+            fake_program(&mpl_core::ID).clone(),
+            fake_program(&nifty_asset::ID).clone(),
+        ];
+        for (i, asset_info) in asset_infos.into_iter().enumerate() {
+            if missing != Some(i) {
+                remaining_accounts.push(asset_info.clone());
+            }
+        }
+
+        (nicos, remaining_accounts.leak())
+    }
+
+    fn transfer_many(
+        nicos: &[Nico<'static>],
+        mode: NicoBatchMode,
+        remaining_accounts: &'static [AccountInfo<'static>],
+    ) -> (Result<Vec<NicoTransferResult>, ProgramError>, Vec<Pubkey>) {
+        let (result, invokes) = record_invokes(|| {
+            Nico::transfer_many(
+                nicos,
+                fake_wallet(),
+                &[fake_wallet()],
+                None,
+                mode,
+                remaining_accounts,
+                &[],
+            )
+        });
+
+        (result, invokes.iter().map(|x| x.program_id).collect())
+    }
+
+    #[test]
+    fn transfers_by_standard_and_reports_in_order() {
+        let (nicos, remaining_accounts) = batch(None);
+
+        let (result, programs) = transfer_many(&nicos, NicoBatchMode::Atomic, remaining_accounts);
+
+        assert_eq!(
+            programs,
+            [nifty_asset::ID, nifty_asset::ID, mpl_core::ID, mpl_core::ID]
+        );
+        let results = result.unwrap();
+        assert_eq!(
+            results.iter().map(|x| x.nico).collect::<Vec<_>>(),
+            nicos.iter().map(|x| x.pubkey).collect::<Vec<_>>()
+        );
+        assert!(results.iter().all(|x| x.result.is_ok()));
+    }
+
+    #[test]
+    fn best_effort_reports_failure_and_carries_on() {
+        // the first nifty asset, transferred first
+        let (nicos, remaining_accounts) = batch(Some(1));
+
+        let (result, programs) =
+            transfer_many(&nicos, NicoBatchMode::BestEffort, remaining_accounts);
+
+        assert_eq!(programs, [nifty_asset::ID, mpl_core::ID, mpl_core::ID]);
+        let results: Vec<_> = result.unwrap().into_iter().map(|x| x.result).collect();
+        assert_eq!(
+            results,
+            [
+                Ok(()),
+                Err(Error::MissingRemainingAccount.into()),
+                Ok(()),
+                Ok(())
+            ]
+        );
+    }
+
+    #[test]
+    fn atomic_stops_at_failure() {
+        // the second nifty asset, after the first and before the core ones
+        let (nicos, remaining_accounts) = batch(Some(3));
+
+        let (result, programs) = transfer_many(&nicos, NicoBatchMode::Atomic, remaining_accounts);
+
+        assert_eq!(programs, [nifty_asset::ID]);
+        assert_eq!(result, Err(Error::MissingRemainingAccount.into()));
+    }
+
+    #[test]
+    fn rejects_recipient_count_mismatch() {
+        let (nicos, remaining_accounts) = batch(None);
+
+        let result = Nico::transfer_many(
+            &nicos,
+            fake_wallet(),
+            &[fake_wallet(), fake_wallet()],
+            None,
+            NicoBatchMode::BestEffort,
+            remaining_accounts,
+            &[],
+        );
+
+        assert_eq!(result, Err(Error::RecipientCountMismatch.into()));
+    }
+}
@@ -2,10 +2,12 @@ use nifty_asset::{
     instructions::TransferCpi as NiftyTransferCpi, types::Standard as NiftyStandard,
 };
 use nifty_asset_types::state::Asset;
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
 
 use crate::{
-    assertions::assert_same_pubkeys, find_account_or_panic, find_in_remaining_accounts, Error,
+    assertions::assert_same_pubkeys, Error,
     NicoTransferParams,
 };
 
@@ -18,25 +20,28 @@ pub struct TransferNiftyParams<'a, 'b> {
     pub signer_seeds: &'b [&'b [&'b [u8]]],
 }
 
-impl<'a, 'b, 'c> TransferNiftyParams<'a, 'b> {
+impl<'a, 'b> TransferNiftyParams<'a, 'b> {
     pub fn from_nico_transfer_params(
         asset_info: &'a AccountInfo<'a>,
         params: &NicoTransferParams<'a, 'b>,
         remaining_accounts: &'a [AccountInfo<'a>],
-    ) -> TransferNiftyParams<'a, 'b> {
-        let nifty_program_info =
-            find_account_or_panic(&nifty_asset::ID, remaining_accounts, "nifty_asset");
-        TransferNiftyParams {
+    ) -> Result<TransferNiftyParams<'a, 'b>, ProgramError> {
+        let nifty_program_info = params.find(
+            &nifty_asset::ID,
+            remaining_accounts,
+            "nifty_asset",
+        )?;
+        Ok(TransferNiftyParams {
             nifty_program_info,
-            signer_info: match params.authority_info {
+            signer_info: match params.base.authority_info {
                 Some(x) => x,
-                _ => params.payer_info,
+                _ => params.base.payer_info,
             },
             asset_info,
             recipient_info: params.recipient_info,
-            group_asset_opt_info: params.group_asset_opt_info,
-            signer_seeds: params.signer_seeds,
-        }
+            group_asset_opt_info: params.base.group_asset_opt_info,
+            signer_seeds: params.base.signer_seeds,
+        })
     }
 }
 
@@ -57,7 +62,7 @@ pub fn check_and_transfer_nifty(params: TransferNiftyParams<'_, '_>) -> ProgramR
         &nifty_asset::ID,
     )?;
 
-    let data = asset_info.try_borrow_data().unwrap();
+    let data = asset_info.try_borrow_data()?;
 
     // Must have the expected amount of data and the correct discriminator and standard.
     if data.len() < Asset::LEN || data[2] != NiftyStandard::NonFungible as u8 {
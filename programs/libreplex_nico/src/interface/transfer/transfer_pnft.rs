@@ -1,19 +1,15 @@
-use std::str::FromStr;
-
-use anchor_lang::AnchorDeserialize;
-use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 use libreplex_shared::sysvar_instructions_program;
-use mpl_token_metadata::{
-    accounts::Metadata,
-    instructions::{TransferV1Cpi, TransferV1InstructionArgs},
-};
+use mpl_token_metadata::instructions::{TransferV1Cpi, TransferV1InstructionArgs};
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey, system_program,
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    pubkey::Pubkey, system_program,
 };
 
 use crate::{
-    assertions::assert_same_pubkeys, find_account_or_panic, Error,
-    NicoTransferParams,
+    assertions::assert_same_pubkeys, associated_token_address, associated_token_address_with_bump,
+    edition_address, edition_address_with_bump, metadata_address, metadata_address_with_bump,
+    read_rule_set, token_record_address, token_record_address_with_bump, NicoTransferParams,
+    AUTH_RULES_PROGRAM_ID,
 };
 
 pub const AUTH_RULES: &str = "auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg";
@@ -41,7 +37,26 @@ pub struct TransferPnftParams<'a, 'b> {
     pub signer_seeds: &'b [&'b [&'b [u8]]],
 }
 
-impl<'a, 'b, 'c> TransferPnftParams<'a, 'b> {
+/// Bumps of the pNFT transfer accounts, worked out off-chain so that the
+/// program only has to check them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NicoPnftBumps {
+    pub metadata: u8,
+    pub edition: u8,
+    pub target_token_account: u8,
+    pub source_token_record: u8,
+    pub target_token_record: u8,
+}
+
+struct PnftAddresses {
+    metadata: Pubkey,
+    edition: Pubkey,
+    target_token_account: Pubkey,
+    source_token_record: Pubkey,
+    target_token_record: Pubkey,
+}
+
+impl<'a, 'b> TransferPnftParams<'a, 'b> {
     pub fn from_nico_transfer_params(
         nico_asset_info: &'a AccountInfo<'a>,
         current_owner: &'a AccountInfo<'a>,
@@ -51,135 +66,171 @@ impl<'a, 'b, 'c> TransferPnftParams<'a, 'b> {
         // that cannot be derived if it's not an ATA
         // target token accounts are forced to use ATA
         remaining_accounts: &'a [AccountInfo<'a>],
-    ) -> TransferPnftParams<'a, 'b> {
+    ) -> Result<TransferPnftParams<'a, 'b>, ProgramError> {
+        let target_token_account = associated_token_address(
+            params.recipient_info.key,
+            &params.base.nico_pubkey,
+            &params.base.nico_owner_program,
+        );
+
+        let addresses = PnftAddresses {
+            metadata: metadata_address(&params.base.nico_pubkey),
+            edition: edition_address(&params.base.nico_pubkey),
+            source_token_record: token_record_address(
+                &params.base.nico_pubkey,
+                current_token_account.key,
+            ),
+            target_token_record: token_record_address(
+                &params.base.nico_pubkey,
+                &target_token_account,
+            ),
+            target_token_account,
+        };
+
+        Self::from_addresses(
+            nico_asset_info,
+            current_owner,
+            current_token_account,
+            params,
+            addresses,
+            remaining_accounts,
+        )
+    }
+
+    /// Same as [`Self::from_nico_transfer_params`], but checks the bumps
+    /// given by the client instead of searching for them. Saves the bulk
+    /// of the compute spent on a pNFT transfer.
+    ///
+    /// [`Nico::transfer`](crate::Nico::transfer) does not take bumps and
+    /// always searches. This is for programs building the pNFT transfer CPI
+    /// themselves, passing the result to [`check_and_transfer_pnft`].
+    pub fn from_nico_transfer_params_with_bumps(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_owner: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoTransferParams<'a, 'b>,
+        bumps: NicoPnftBumps,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<TransferPnftParams<'a, 'b>, ProgramError> {
+        let target_token_account = associated_token_address_with_bump(
+            params.recipient_info.key,
+            &params.base.nico_pubkey,
+            &params.base.nico_owner_program,
+            bumps.target_token_account,
+        )?;
+
+        let addresses = PnftAddresses {
+            metadata: metadata_address_with_bump(&params.base.nico_pubkey, bumps.metadata)?,
+            edition: edition_address_with_bump(&params.base.nico_pubkey, bumps.edition)?,
+            source_token_record: token_record_address_with_bump(
+                &params.base.nico_pubkey,
+                current_token_account.key,
+                bumps.source_token_record,
+            )?,
+            target_token_record: token_record_address_with_bump(
+                &params.base.nico_pubkey,
+                &target_token_account,
+                bumps.target_token_record,
+            )?,
+            target_token_account,
+        };
+
+        Self::from_addresses(
+            nico_asset_info,
+            current_owner,
+            current_token_account,
+            params,
+            addresses,
+            remaining_accounts,
+        )
+    }
+
+    fn from_addresses(
+        nico_asset_info: &'a AccountInfo<'a>,
+        current_owner: &'a AccountInfo<'a>,
+        current_token_account: &'a AccountInfo<'a>,
+        params: &NicoTransferParams<'a, 'b>,
+        addresses: PnftAddresses,
+        remaining_accounts: &'a [AccountInfo<'a>],
+    ) -> Result<TransferPnftParams<'a, 'b>, ProgramError> {
         // need to derive extra system account
 
-        let system_program_info =
-            find_account_or_panic(&system_program::ID, remaining_accounts, "system_program");
+        let system_program_info = params.find(
+            &system_program::ID,
+            remaining_accounts,
+            "system_program",
+        )?;
 
-        let mpl_token_program_info = find_account_or_panic(
+        let mpl_token_program_info = params.find(
             &mpl_token_metadata::ID,
             remaining_accounts,
             "mpl_token_metadata",
-        );
+        )?;
 
-        let token_program = find_account_or_panic(
-            &params.nico_owner_program,
+        let token_program = params.find(
+            &params.base.nico_owner_program,
             remaining_accounts,
             "token_program",
-        );
-        let spl_ata_program = find_account_or_panic(
+        )?;
+        let spl_ata_program = params.find(
             &spl_associated_token_account::ID,
             remaining_accounts,
             "associated_token_program",
-        );
-        let auth_rules_program_info = find_account_or_panic(
-            &Pubkey::from_str(AUTH_RULES).unwrap(),
+        )?;
+        let auth_rules_program_info = params.find(
+            &AUTH_RULES_PROGRAM_ID,
             remaining_accounts,
             "auth_rules_program",
-        );
+        )?;
 
-        let metadata_info = find_account_or_panic(
-            &Pubkey::find_program_address(
-                &[
-                    "metadata".as_bytes(),
-                    &mpl_token_metadata::ID.as_ref(),
-                    params.nico_pubkey.as_ref(),
-                ],
-                &mpl_token_metadata::ID,
-            )
-            .0,
-            remaining_accounts,
-            "metadata",
-        );
+        let metadata_info =
+            params
+                .base
+                .accounts
+                .find(&addresses.metadata, remaining_accounts, "metadata")?;
 
-        let mut bytes: &[u8] = &(*metadata_info.data).borrow();
-        let metadata = Metadata::deserialize(&mut bytes)
-            .map_err(|error| {
-                msg!("Error: {}", error);
-                Error::DeserializationError
-            })
-            .unwrap();
-
-        let auth_rules_info = match metadata.programmable_config {
-            Some(x) => match &x {
-                mpl_token_metadata::types::ProgrammableConfig::V1 { rule_set } => *rule_set,
-            },
-            None => None,
-        }
-        .map(|x| find_account_or_panic(&x, remaining_accounts, "auth_rule"));
-
-        let sysvar_instruction_info = find_account_or_panic(
+        let auth_rules_info = read_rule_set(&metadata_info.try_borrow_data()?)?
+            .map(|x| params.base.accounts.find(&x, remaining_accounts, "auth_rule"))
+            .transpose()?;
+
+        let sysvar_instruction_info = params.find(
             &sysvar_instructions_program::ID,
             remaining_accounts,
             "sysvar_instructions_program",
-        );
+        )?;
 
-        let target_token_account_info = find_account_or_panic(
-            &get_associated_token_address_with_program_id(
-                &params.recipient_info.key,
-                &params.nico_pubkey,
-                &params.nico_owner_program,
-            ),
+        let target_token_account_info = params.base.accounts.find(
+            &addresses.target_token_account,
             remaining_accounts,
             "target_ata",
-        );
+        )?;
 
-        let source_token_record_info = find_account_or_panic(
-            &Pubkey::find_program_address(
-                &[
-                    b"metadata",
-                    mpl_token_metadata::ID.as_ref(),
-                    params.nico_pubkey.as_ref(),
-                    b"token_record",
-                    current_token_account.key.as_ref(),
-                ],
-                &mpl_token_metadata::ID,
-            )
-            .0,
+        let source_token_record_info = params.base.accounts.find(
+            &addresses.source_token_record,
             remaining_accounts,
             "source_token_record",
-        );
+        )?;
 
-        let target_token_record_info = find_account_or_panic(
-            &Pubkey::find_program_address(
-                &[
-                    b"metadata",
-                    mpl_token_metadata::ID.as_ref(),
-                    params.nico_pubkey.as_ref(),
-                    b"token_record",
-                    target_token_account_info.key.as_ref(),
-                ],
-                &mpl_token_metadata::ID,
-            )
-            .0,
+        let target_token_record_info = params.base.accounts.find(
+            &addresses.target_token_record,
             remaining_accounts,
             "target_token_record",
-        );
+        )?;
 
-        let edition_info = find_account_or_panic(
-            &Pubkey::find_program_address(
-                &[
-                    "metadata".as_bytes(),
-                    &mpl_token_metadata::ID.as_ref(),
-                    params.nico_pubkey.as_ref(),
-                    "edition".as_bytes(),
-                ],
-                &mpl_token_metadata::ID,
-            )
-            .0,
-            remaining_accounts,
-            "master_edition",
-        );
+        let edition_info =
+            params
+                .base
+                .accounts
+                .find(&addresses.edition, remaining_accounts, "master_edition")?;
 
-        TransferPnftParams {
+        Ok(TransferPnftParams {
             mpl_token_program_info,
-            authority_info: params.authority_info,
+            authority_info: params.base.authority_info,
             asset_info: nico_asset_info,
             new_owner_info: params.recipient_info,
-            // collection_asset_opt_info: params.group_asset_opt_info,
-            signer_seeds: params.signer_seeds,
-            payer_info: params.payer_info,
+            // collection_asset_opt_info: params.base.group_asset_opt_info,
+            signer_seeds: params.base.signer_seeds,
+            payer_info: params.base.payer_info,
             system_program_info,
             source_token_account_info: current_token_account,
             current_owner,
@@ -193,7 +244,7 @@ impl<'a, 'b, 'c> TransferPnftParams<'a, 'b> {
             spl_ata_program,
             auth_rules_program_info,
             auth_rules_info,
-        }
+        })
     }
 }
 
@@ -229,13 +280,13 @@ pub fn check_and_transfer_pnft(params: TransferPnftParams<'_, '_>) -> ProgramRes
 
     assert_same_pubkeys("system_program", system_program_info, &system_program::ID)?;
 
-    let data = asset_info.try_borrow_data().unwrap();
+    let data = asset_info.try_borrow_data()?;
 
     // Drop the data reference before the CPI.
     drop(data);
 
 
-    let metadata_ref = metadata.try_borrow_data().unwrap();
+    let metadata_ref = metadata.try_borrow_data()?;
 
     // Drop the data reference before the CPI.
     drop(metadata_ref);
@@ -7,3 +7,8 @@ pub use error::*;
 pub mod interface;
 pub use interface::*;
 
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,460 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use mpl_core::{
+    accounts::{BaseAssetV1, PluginHeaderV1, PluginRegistryV1},
+    types::{Key as CoreKey, Plugin, PluginAuthority, PluginType, RegistryRecord, UpdateAuthority},
+};
+use mpl_token_metadata::{
+    accounts::{Metadata, TokenRecord},
+    types::{Collection, Key as MetadataKey, TokenStandard, TokenState},
+};
+use nifty_asset::{
+    accounts::Asset,
+    types::{Delegate, Discriminator, Standard, State},
+    DelegateRoles, NullablePublicKey,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Epoch,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_option::COption,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use spl_token_2022::state::{Account as TokenAccount, AccountState, Mint};
+use std::{cell::RefCell, sync::Once};
+
+use crate::{metadata_address, token_record_address};
+
+// Builders for the accounts Nico classifies, so that code built on Nico can
+// be unit tested without a runtime. The accounts are leaked, so they live
+// for the rest of the test like the accounts passed to a program, and can
+// be handed straight to `Nico::from_raw_data`.
+
+pub(crate) fn fake_account(
+    key: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+) -> &'static AccountInfo<'static> {
+    let lamports = Rent::default().minimum_balance(data.len());
+
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        true,
+        Box::leak(Box::new(lamports)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        Epoch::default(),
+    )))
+}
+
+/// A system account that signs, for payers, authorities and owners.
+pub fn fake_wallet() -> &'static AccountInfo<'static> {
+    let info = fake_account(Pubkey::new_unique(), system_program::ID, vec![]);
+    let mut signer = info.clone();
+    signer.is_signer = true;
+
+    Box::leak(Box::new(signer))
+}
+
+/// The executable account of `program_id`, for the programs Nico invokes.
+pub fn fake_program(program_id: &Pubkey) -> &'static AccountInfo<'static> {
+    let mut info = fake_account(*program_id, Pubkey::new_unique(), vec![]).clone();
+    info.executable = true;
+
+    Box::leak(Box::new(info))
+}
+
+thread_local! {
+    static INVOKES: RefCell<Vec<Instruction>> = const { RefCell::new(vec![]) };
+}
+
+// off chain, invokes go to the syscall stubs. these ones record the
+// instruction on the calling thread and succeed without running it
+struct RecordingStubs;
+
+impl SyscallStubs for RecordingStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        INVOKES.with(|invokes| invokes.borrow_mut().push(instruction.clone()));
+        Ok(())
+    }
+}
+
+/// Runs `f` and returns its result along with the instructions it invoked,
+/// in order. The invoked programs do not run, so the accounts are left as
+/// they were.
+pub fn record_invokes<T>(f: impl FnOnce() -> T) -> (T, Vec<Instruction>) {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(RecordingStubs));
+    });
+
+    INVOKES.with(|invokes| invokes.borrow_mut().clear());
+    let result = f();
+
+    (result, INVOKES.with(|invokes| invokes.take()))
+}
+
+fn nullable_pubkey(pubkey: Option<&Pubkey>) -> NullablePublicKey {
+    // an all zero key reads as none
+    NullablePublicKey::try_from_slice(pubkey.unwrap_or(&Pubkey::default()).as_ref()).unwrap()
+}
+
+/// A non-fungible Nifty asset owned by `owner`, in `group` if given.
+pub fn fake_nifty_asset(owner: &Pubkey, group: Option<&Pubkey>) -> &'static AccountInfo<'static> {
+    let asset = Asset {
+        discriminator: Discriminator::Asset,
+        state: State::Unlocked,
+        standard: Standard::NonFungible,
+        mutable: true,
+        owner: *owner,
+        group: nullable_pubkey(group),
+        authority: Pubkey::new_unique(),
+        delegate: Delegate {
+            address: nullable_pubkey(None),
+            roles: DelegateRoles::try_from_slice(&[0]).unwrap(),
+        },
+        name: [0; 35],
+    };
+
+    fake_account(
+        Pubkey::new_unique(),
+        nifty_asset::ID,
+        asset.try_to_vec().unwrap(),
+    )
+}
+
+/// An mpl-core asset. It is in a collection when `update_authority` is
+/// [`UpdateAuthority::Collection`].
+pub fn fake_core_asset(update_authority: UpdateAuthority) -> &'static AccountInfo<'static> {
+    fake_core_asset_with_plugins(update_authority, vec![])
+}
+
+/// An mpl-core asset with the given plugins, all under the update
+/// authority.
+pub fn fake_core_asset_with_plugins(
+    update_authority: UpdateAuthority,
+    plugins: Vec<Plugin>,
+) -> &'static AccountInfo<'static> {
+    let asset = BaseAssetV1 {
+        key: CoreKey::AssetV1,
+        owner: Pubkey::new_unique(),
+        update_authority,
+        name: String::new(),
+        uri: String::new(),
+        seq: None,
+    };
+
+    let mut data = asset.try_to_vec().unwrap();
+    if !plugins.is_empty() {
+        // the header, the plugins, then the registry the header points to
+        let header_offset = data.len();
+        data.resize(header_offset + PluginHeaderV1::LEN, 0);
+
+        let mut registry = vec![];
+        for plugin in plugins {
+            registry.push(RegistryRecord {
+                plugin_type: PluginType::from(&plugin),
+                authority: PluginAuthority::UpdateAuthority,
+                offset: data.len() as u64,
+            });
+            data.extend(plugin.try_to_vec().unwrap());
+        }
+
+        let header = PluginHeaderV1 {
+            key: CoreKey::PluginHeaderV1,
+            plugin_registry_offset: data.len() as u64,
+        };
+        data[header_offset..header_offset + PluginHeaderV1::LEN]
+            .copy_from_slice(&header.try_to_vec().unwrap());
+        data.extend(
+            PluginRegistryV1 {
+                key: CoreKey::PluginRegistryV1,
+                registry,
+                external_registry: vec![],
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+    }
+
+    fake_account(Pubkey::new_unique(), mpl_core::ID, data)
+}
+
+/// A token metadata mint owned by `program`, either spl-token or
+/// token-2022, and its metadata account, returned as `(mint, metadata)`. A
+/// token standard of `None` is what old NFTs have.
+pub fn fake_metadata(
+    program: &Pubkey,
+    token_standard: Option<TokenStandard>,
+    collection: Option<Collection>,
+) -> (&'static AccountInfo<'static>, &'static AccountInfo<'static>) {
+    let mint_info = fake_mint(program);
+
+    let metadata = Metadata {
+        key: MetadataKey::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: *mint_info.key,
+        name: String::new(),
+        symbol: String::new(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard,
+        collection,
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    };
+
+    let metadata_info = fake_account(
+        metadata_address(mint_info.key),
+        mpl_token_metadata::ID,
+        metadata.try_to_vec().unwrap(),
+    );
+
+    (mint_info, metadata_info)
+}
+
+/// An initialized mint with a supply of one and no extensions, owned by
+/// `program`, either spl-token or token-2022.
+pub fn fake_mint(program: &Pubkey) -> &'static AccountInfo<'static> {
+    let mint = Mint {
+        mint_authority: COption::None,
+        supply: 1,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(mint, &mut data).unwrap();
+
+    fake_account(Pubkey::new_unique(), *program, data)
+}
+
+/// A token account of `mint` holding it for `owner`, under `program`,
+/// either spl-token or token-2022.
+pub fn fake_token_account(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    program: &Pubkey,
+    frozen: bool,
+) -> &'static AccountInfo<'static> {
+    let token_account = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount: 1,
+        delegate: COption::None,
+        state: if frozen {
+            AccountState::Frozen
+        } else {
+            AccountState::Initialized
+        },
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(token_account, &mut data).unwrap();
+
+    fake_account(Pubkey::new_unique(), *program, data)
+}
+
+/// The token record of a pNFT held in `token_account`, in `state`.
+pub fn fake_token_record(
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    state: TokenState,
+) -> &'static AccountInfo<'static> {
+    let token_record = TokenRecord {
+        key: MetadataKey::TokenRecord,
+        bump: 0,
+        state,
+        rule_set_revision: None,
+        delegate: None,
+        delegate_role: None,
+        locked_transfer: None,
+    };
+
+    let mut data = token_record.try_to_vec().unwrap();
+    data.resize(TokenRecord::LEN, 0);
+
+    fake_account(
+        token_record_address(mint, token_account),
+        mpl_token_metadata::ID,
+        data,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, MetadataType, Nico, NicoType};
+
+    use super::*;
+
+    fn metadata_type(nico: &Nico) -> Option<MetadataType> {
+        match nico.nico_type {
+            NicoType::Mint { metadata, .. } => Some(metadata),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn classifies_nifty_asset() {
+        let group = Pubkey::new_unique();
+
+        let nico = Nico::try_from_accounts(
+            fake_nifty_asset(&Pubkey::new_unique(), Some(&group)),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(nico.nico_type, NicoType::Nifty));
+        assert_eq!(nico.group, Some(group));
+
+        let nico = Nico::try_from_accounts(
+            fake_nifty_asset(&Pubkey::new_unique(), None),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(nico.group, None);
+    }
+
+    #[test]
+    fn classifies_core_asset() {
+        let collection = Pubkey::new_unique();
+
+        let nico = Nico::try_from_accounts(
+            fake_core_asset(UpdateAuthority::Collection(collection)),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(nico.nico_type, NicoType::MxCore));
+        assert_eq!(nico.group, Some(collection));
+
+        let nico = Nico::try_from_accounts(
+            fake_core_asset(UpdateAuthority::Address(Pubkey::new_unique())),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(nico.group, None);
+    }
+
+    #[test]
+    fn classifies_metadata_token_standards() {
+        let cases = [
+            (
+                Some(TokenStandard::ProgrammableNonFungible),
+                MetadataType::Mxprogrammable,
+            ),
+            (
+                Some(TokenStandard::NonFungible),
+                MetadataType::MxNonProgrammable,
+            ),
+            (None, MetadataType::Unknown),
+        ];
+
+        for program in [spl_token::ID, spl_token_2022::ID] {
+            for (token_standard, expected) in cases.clone() {
+                let (mint_info, metadata_info) =
+                    fake_metadata(&program, token_standard.clone(), None);
+
+                let nico =
+                    Nico::try_from_accounts(mint_info, Some(metadata_info), None, None).unwrap();
+                assert_eq!(metadata_type(&nico), Some(expected), "{:?}", token_standard);
+                assert_eq!(nico.owner_program, program);
+                assert_eq!(nico.group, None);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_only_verified_collections() {
+        let collection = Pubkey::new_unique();
+
+        for verified in [true, false] {
+            let (mint_info, metadata_info) = fake_metadata(
+                &spl_token::ID,
+                Some(TokenStandard::ProgrammableNonFungible),
+                Some(Collection {
+                    verified,
+                    key: collection,
+                }),
+            );
+
+            let nico = Nico::try_from_accounts(mint_info, Some(metadata_info), None, None).unwrap();
+            assert_eq!(nico.group, verified.then_some(collection));
+        }
+    }
+
+    // a worthless mint paired with the metadata of a collection member
+    #[test]
+    fn rejects_metadata_of_another_mint() {
+        let collection = Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        };
+        let (_, member_metadata) = fake_metadata(
+            &spl_token::ID,
+            Some(TokenStandard::NonFungible),
+            Some(collection),
+        );
+        let mint_info = fake_mint(&spl_token::ID);
+
+        let result = Nico::try_from_accounts(mint_info, Some(member_metadata), None, None);
+        assert!(matches!(result, Err(Error::InvalidMetadataAccount)));
+
+        // the same data at the address of the worthless mint
+        let copied_metadata = fake_account(
+            metadata_address(mint_info.key),
+            mpl_token_metadata::ID,
+            member_metadata.data.borrow().to_vec(),
+        );
+        let result = Nico::try_from_accounts(mint_info, Some(copied_metadata), None, None);
+        assert!(matches!(result, Err(Error::InvalidMetadataAccount)));
+    }
+
+    #[test]
+    fn rejects_metadata_not_owned_by_token_metadata() {
+        let (mint_info, metadata_info) =
+            fake_metadata(&spl_token::ID, Some(TokenStandard::NonFungible), None);
+        let forged_metadata = fake_account(
+            *metadata_info.key,
+            Pubkey::new_unique(),
+            metadata_info.data.borrow().to_vec(),
+        );
+
+        let result = Nico::try_from_accounts(mint_info, Some(forged_metadata), None, None);
+        assert!(matches!(result, Err(Error::InvalidMetadataAccount)));
+    }
+
+    #[test]
+    fn requires_metadata_for_token_22_mint_without_pointer() {
+        // no metadata pointer, so it falls back to token metadata
+        let result = Nico::try_from_accounts(fake_mint(&spl_token_2022::ID), None, None, None);
+
+        assert!(matches!(result, Err(Error::MissingMetadataAccount)));
+    }
+}
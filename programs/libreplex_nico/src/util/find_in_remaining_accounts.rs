@@ -1,13 +1,25 @@
-use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
 
-use crate::AccountData;
+use crate::{AccountData, Error};
 
+/// Find the given account in the remaining accounts, logging the account
+/// name and expected key if it is missing.
 pub fn find_account<'a>(
     account_id: &Pubkey,
     remaining_accounts: &'a [AccountInfo<'a>],
     name: &str
-) -> Option<&'a AccountInfo<'a>> {
-    remaining_accounts.iter().find(|x| x.key.eq(account_id))
+) -> Result<&'a AccountInfo<'a>, ProgramError> {
+    remaining_accounts
+        .iter()
+        .find(|x| x.key.eq(account_id))
+        .ok_or_else(|| {
+            msg!(
+                "Account \"{}\" [{}] not found in remaining accounts",
+                name,
+                account_id
+            );
+            Error::MissingRemainingAccount.into()
+        })
 }
 
 pub fn find_account_data<'a>(
@@ -16,18 +28,3 @@ pub fn find_account_data<'a>(
 ) -> Option<&'a AccountData<'a>> {
     account_datas.into_iter().find(|x| x.pubkey.eq(account_id))
 }
-
-pub fn find_account_or_panic<'a>(
-    account_id: &Pubkey,
-    remaining_accounts: &'a [AccountInfo<'a>],
-    name: &str
-) -> &'a AccountInfo<'a> {
-    if let Some(x) = find_account(account_id, remaining_accounts, name) {
-        x
-    } else {
-        panic!(
-            "Account {} ({}) not found in remaining accounts",
-            account_id, name
-        );
-    }
-}
@@ -2,4 +2,6 @@ pub mod find_in_remaining_accounts;
 pub use find_in_remaining_accounts::*;
 
 pub mod assertions;
-pub use assertions::*;
\ No newline at end of file
+pub use assertions::*;
+pub mod programmable_config;
+pub use programmable_config::*;
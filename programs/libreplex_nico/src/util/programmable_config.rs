@@ -0,0 +1,260 @@
+use mpl_token_metadata::types::Key;
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::Error;
+
+// Walks the borsh layout of a metadata account without allocating.
+struct MetadataReader<'d> {
+    data: &'d [u8],
+    offset: usize,
+}
+
+impl<'d> MetadataReader<'d> {
+    fn take(&mut self, len: usize) -> Result<&'d [u8], ProgramError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .ok_or(Error::DeserializationError)?;
+        let bytes = self
+            .data
+            .get(self.offset..end)
+            .ok_or(Error::DeserializationError)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn skip_string(&mut self) -> Result<(), ProgramError> {
+        let len = self.read_u32()? as usize;
+        self.take(len).map(|_| ())
+    }
+
+    // borsh only accepts 0 and 1 as option tags
+    fn read_option(&mut self) -> Result<bool, ProgramError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => {
+                msg!("Invalid option tag {}", tag);
+                Err(Error::DeserializationError.into())
+            }
+        }
+    }
+
+    // skips the payload if the option is set
+    fn skip_option(&mut self, len: usize) -> Result<(), ProgramError> {
+        if self.read_option()? {
+            self.take(len)?;
+        }
+        Ok(())
+    }
+
+    // older accounts may end before the trailing options
+    fn at_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+}
+
+/// Read the token auth rule set of a pNFT from its metadata account data.
+/// Only the `programmable_config` is decoded, the fields before it are
+/// skipped, which is much cheaper than deserializing the whole account.
+pub fn read_rule_set(data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    let mut reader = MetadataReader { data, offset: 0 };
+
+    if reader.read_u8()? != Key::MetadataV1 as u8 {
+        msg!("Account is not a metadata account");
+        return Err(Error::DeserializationError.into());
+    }
+
+    // update authority, mint
+    reader.take(64)?;
+    // name, symbol, uri
+    reader.skip_string()?;
+    reader.skip_string()?;
+    reader.skip_string()?;
+    // seller fee basis points
+    reader.take(2)?;
+    // creators: address, verified, share
+    if reader.read_option()? {
+        let count = reader.read_u32()? as usize;
+        reader.take(count.checked_mul(34).ok_or(Error::DeserializationError)?)?;
+    }
+    // primary sale happened, is mutable
+    reader.take(2)?;
+    // edition nonce, token standard, collection, uses
+    reader.skip_option(1)?;
+    reader.skip_option(1)?;
+    reader.skip_option(33)?;
+    reader.skip_option(17)?;
+
+    if reader.at_end() {
+        return Ok(None);
+    }
+    // collection details: variant, size
+    reader.skip_option(9)?;
+
+    if reader.at_end() || !reader.read_option()? {
+        return Ok(None);
+    }
+    // ProgrammableConfig::V1 is the only variant
+    if reader.read_u8()? != 0 {
+        msg!("Unknown programmable config variant");
+        return Err(Error::DeserializationError.into());
+    }
+    if !reader.read_option()? {
+        return Ok(None);
+    }
+    let rule_set = Pubkey::try_from(reader.take(32)?).map_err(|_| Error::DeserializationError)?;
+    Ok(Some(rule_set))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorSerialize;
+    use mpl_token_metadata::{
+        accounts::Metadata,
+        types::{
+            Collection, CollectionDetails, Creator, ProgrammableConfig, TokenStandard, UseMethod,
+            Uses,
+        },
+    };
+
+    use super::*;
+
+    // size metadata accounts are allocated with
+    const MAX_METADATA_LEN: usize = 679;
+
+    fn metadata(
+        creators: bool,
+        collection: bool,
+        uses: bool,
+        collection_details: bool,
+        programmable_config: Option<Option<Pubkey>>,
+    ) -> Metadata {
+        Metadata {
+            key: Key::MetadataV1,
+            update_authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            name: "Nico".to_string(),
+            symbol: "NICO".to_string(),
+            uri: "https://example.com/nico.json".to_string(),
+            seller_fee_basis_points: 500,
+            creators: creators.then(|| {
+                vec![
+                    Creator {
+                        address: Pubkey::new_unique(),
+                        verified: true,
+                        share: 60,
+                    },
+                    Creator {
+                        address: Pubkey::new_unique(),
+                        verified: false,
+                        share: 40,
+                    },
+                ]
+            }),
+            primary_sale_happened: true,
+            is_mutable: true,
+            edition_nonce: Some(254),
+            token_standard: Some(TokenStandard::ProgrammableNonFungible),
+            collection: collection.then(|| Collection {
+                verified: true,
+                key: Pubkey::new_unique(),
+            }),
+            uses: uses.then_some(Uses {
+                use_method: UseMethod::Multiple,
+                remaining: 3,
+                total: 5,
+            }),
+            collection_details: collection_details.then_some(CollectionDetails::V1 { size: 10 }),
+            programmable_config: programmable_config
+                .map(|rule_set| ProgrammableConfig::V1 { rule_set }),
+        }
+    }
+
+    // metadata accounts are allocated at the maximum size, zero padded
+    fn account_data(metadata: &Metadata) -> Vec<u8> {
+        let mut data = metadata.try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+        data
+    }
+
+    fn expected_rule_set(data: &[u8]) -> Option<Pubkey> {
+        match Metadata::safe_deserialize(data)
+            .unwrap()
+            .programmable_config
+        {
+            Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+            None => None,
+        }
+    }
+
+    #[test]
+    fn matches_safe_deserialize() {
+        let programmable_configs = [None, Some(None), Some(Some(Pubkey::new_unique()))];
+
+        for fields in 0..16 {
+            for programmable_config in programmable_configs {
+                let metadata = metadata(
+                    fields & 1 != 0,
+                    fields & 2 != 0,
+                    fields & 4 != 0,
+                    fields & 8 != 0,
+                    programmable_config,
+                );
+                let data = account_data(&metadata);
+
+                assert_eq!(
+                    read_rule_set(&data).unwrap(),
+                    expected_rule_set(&data),
+                    "fields {:04b}, programmable config {:?}",
+                    fields,
+                    programmable_config
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reads_accounts_ending_before_trailing_fields() {
+        let metadata = metadata(true, true, true, false, None);
+        let data = metadata.try_to_vec().unwrap();
+        // cut off the collection details and programmable config tags
+        let data = &data[..data.len() - 2];
+
+        assert_eq!(read_rule_set(data).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_invalid_option_tag() {
+        let metadata = metadata(false, false, false, false, Some(None));
+        let mut data = metadata.try_to_vec().unwrap();
+        // programmable config tag, variant, rule set tag
+        let tag = data.len() - 3;
+        data[tag] = 2;
+
+        assert_eq!(
+            read_rule_set(&data),
+            Err(Error::DeserializationError.into())
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_rule_set() {
+        let metadata = metadata(false, false, false, false, Some(Some(Pubkey::new_unique())));
+        let data = metadata.try_to_vec().unwrap();
+
+        assert_eq!(
+            read_rule_set(&data[..data.len() - 1]),
+            Err(Error::DeserializationError.into())
+        );
+    }
+}
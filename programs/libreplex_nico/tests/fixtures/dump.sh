@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+# Dumps the programs the integration tests load from mainnet. The dumped
+# binaries are checked in next to this script so the tests run offline.
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+URL=${URL:-https://api.mainnet-beta.solana.com}
+
+solana program dump -u "$URL" AssetGtQBTSgm5s91d1RAQod5JmaZiJDxqsgtqrZud73 nifty_asset.so
+solana program dump -u "$URL" CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d mpl_core.so
+solana program dump -u "$URL" metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so
+solana program dump -u "$URL" auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg mpl_token_auth_rules.so
@@ -0,0 +1,477 @@
+//! End to end tests against the Nifty, Core, Token Metadata and Token Auth
+//! Rules programs, loaded from the binaries in `tests/fixtures` (see
+//! `dump.sh` there). A small wrapper program, run natively, creates and
+//! transfers assets through the Nico interface, and the tests classify the
+//! resulting accounts with `Nico::from_raw_data`.
+//!
+//! Each test loads only the binaries of its standard. Token-2022 runs
+//! natively from the `spl-token-2022` crate, so its tests need no fixtures.
+//!
+//! Compressed Nicos are the one `NicoType` not covered: Nico cannot create
+//! them, so the tests would also need the Bubblegum, account compression and
+//! noop binaries, a merkle tree to mint into and proofs built off-chain.
+
+use std::path::Path;
+
+use libreplex_nico::{
+    associated_token_address, metadata_address, token_record_address, MetadataType, Nico,
+    NicoAccountLayout, NicoCreateParams, NicoGroup, NicoStandard, NicoType, AUTH_RULES_PROGRAM_ID,
+};
+use solana_program::{
+    account_info::{AccountInfo, IntoAccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const CREATE: u8 = 0;
+const CREATE_GROUP: u8 = 1;
+const TRANSFER: u8 = 2;
+
+// transfer flags, for the optional accounts used to classify the asset
+const HAS_METADATA: u8 = 1;
+const HAS_TOKEN_ACCOUNT: u8 = 2;
+
+const STANDARDS: [NicoStandard; 5] = [
+    NicoStandard::Nifty,
+    NicoStandard::MxCore,
+    NicoStandard::Token22Extension,
+    NicoStandard::MxNonProgrammable,
+    NicoStandard::Mxprogrammable,
+];
+
+fn standard_index(standard: NicoStandard) -> u8 {
+    STANDARDS.iter().position(|x| *x == standard).unwrap() as u8
+}
+
+// Create and create group: [tag, standard, has group]. Accounts are the
+// asset, payer, owner and group if any, followed by the create layout.
+//
+// Transfer: [tag, flags]. Accounts are the asset, payer (the current owner),
+// recipient and the metadata and token account if flagged, followed by the
+// transfer layout.
+fn process_instruction<'b, 'c>(
+    _program_id: &Pubkey,
+    accounts: &'b [AccountInfo<'c>],
+    data: &[u8],
+) -> ProgramResult {
+    // Nico borrows the accounts for as long as their data, which the
+    // entrypoint signature does not promise
+    let accounts: &'c [AccountInfo<'c>] = Box::leak(accounts.to_vec().into_boxed_slice());
+
+    match data[0] {
+        CREATE | CREATE_GROUP => {
+            let params = NicoCreateParams {
+                standard: STANDARDS[data[1] as usize],
+                name: "Nico".to_string(),
+                uri: "https://example.com/nico.json".to_string(),
+                royalties: 0,
+                creators: vec![],
+                group: (data[2] == 1).then(|| &accounts[3]),
+                owner: &accounts[2],
+            };
+
+            if data[0] == CREATE {
+                Nico::create(
+                    &accounts[0],
+                    params,
+                    &accounts[1],
+                    None,
+                    &accounts[3..],
+                    &[],
+                )?;
+            } else {
+                NicoGroup::create(
+                    &accounts[0],
+                    params,
+                    None,
+                    &accounts[1],
+                    None,
+                    &accounts[3..],
+                    &[],
+                )?;
+            }
+            Ok(())
+        }
+        TRANSFER => {
+            let mut next = 3;
+            let metadata_info = if data[1] & HAS_METADATA != 0 {
+                next += 1;
+                Some(&accounts[next - 1])
+            } else {
+                None
+            };
+            let token_account_info = if data[1] & HAS_TOKEN_ACCOUNT != 0 {
+                next += 1;
+                Some(&accounts[next - 1])
+            } else {
+                None
+            };
+
+            let nico = Nico::try_from_accounts(
+                &accounts[0],
+                metadata_info,
+                Some(&accounts[1]),
+                token_account_info,
+            )?;
+            nico.transfer(
+                &accounts[0],
+                &accounts[1],
+                &accounts[2],
+                None,
+                &accounts[next..],
+                &[],
+            )
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+struct Harness {
+    context: ProgramTestContext,
+    wrapper: Pubkey,
+}
+
+// binaries in tests/fixtures the standard needs, besides the bundled SPL ones
+fn fixtures(standard: NicoStandard) -> Vec<(&'static str, Pubkey)> {
+    match standard {
+        NicoStandard::Nifty => vec![("nifty_asset", nifty_asset::ID)],
+        NicoStandard::MxCore => vec![("mpl_core", mpl_core::ID)],
+        NicoStandard::Token22Extension => vec![],
+        NicoStandard::MxNonProgrammable | NicoStandard::Mxprogrammable => vec![
+            ("mpl_token_metadata", mpl_token_metadata::ID),
+            ("mpl_token_auth_rules", AUTH_RULES_PROGRAM_ID),
+        ],
+    }
+}
+
+impl Harness {
+    async fn new(standard: NicoStandard) -> Harness {
+        let wrapper = Pubkey::new_unique();
+        let mut program_test = ProgramTest::default();
+        // the wrapper runs natively, only the fixtures are binaries
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "libreplex_nico_wrapper",
+            wrapper,
+            processor!(process_instruction),
+        );
+        if standard == NicoStandard::Token22Extension {
+            // the bundled binary cannot create token groups, run the version
+            // Nico is built against
+            program_test.add_program(
+                "spl_token_2022",
+                spl_token_2022::ID,
+                processor!(spl_token_2022::processor::Processor::process),
+            );
+        }
+        program_test.prefer_bpf(true);
+        for (name, program_id) in fixtures(standard) {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures")
+                .join(format!("{}.so", name));
+            assert!(
+                path.exists(),
+                "{} is missing, run tests/fixtures/dump.sh",
+                path.display()
+            );
+            program_test.add_program(name, program_id, None);
+        }
+
+        Harness {
+            context: program_test.start_with_context().await,
+            wrapper,
+        }
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    async fn process(
+        &mut self,
+        accounts: Vec<AccountMeta>,
+        data: Vec<u8>,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            Instruction {
+                program_id: self.wrapper,
+                accounts,
+                data,
+            },
+        ];
+
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    async fn create(&mut self, standard: NicoStandard, group: Option<Pubkey>, tag: u8) -> Pubkey {
+        let asset = Keypair::new();
+        let payer = self.payer();
+
+        let mut accounts = vec![
+            AccountMeta::new(asset.pubkey(), true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(payer, false),
+        ];
+        if let Some(group) = group {
+            accounts.push(AccountMeta::new(group, false));
+        }
+        accounts.extend(
+            NicoAccountLayout::create(standard, &asset.pubkey(), &payer, group).account_metas(),
+        );
+
+        let data = vec![tag, standard_index(standard), group.is_some() as u8];
+        self.process(accounts, data, &[&asset]).await.unwrap();
+
+        asset.pubkey()
+    }
+
+    // leaked so the Nicos built from it can outlive the helpers
+    async fn account_info(&mut self, key: &Pubkey) -> &'static AccountInfo<'static> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*key)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        Box::leak(Box::new(
+            Box::leak(Box::new((*key, account))).into_account_info(),
+        ))
+    }
+
+    async fn classify(&mut self, asset: &Pubkey, owner: &Pubkey) -> Nico<'static> {
+        let asset_info = self.account_info(asset).await;
+        let asset_program = *asset_info.owner;
+
+        let metadata_info = if asset_program == spl_token::ID {
+            Some(self.account_info(&metadata_address(asset)).await)
+        } else {
+            None
+        };
+
+        let (owner_info, token_account_info) =
+            if asset_program == spl_token::ID || asset_program == spl_token_2022::ID {
+                let token_account = associated_token_address(owner, asset, &asset_program);
+                (
+                    Some(self.account_info(owner).await),
+                    Some(self.account_info(&token_account).await),
+                )
+            } else {
+                (None, None)
+            };
+
+        Nico::from_raw_data(asset_info, metadata_info, owner_info, token_account_info)
+    }
+
+    async fn transfer(&mut self, nico: &Nico<'_>, recipient: &Pubkey) {
+        let payer = self.payer();
+
+        let mut accounts = vec![
+            AccountMeta::new(nico.pubkey, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(*recipient, false),
+        ];
+        let mut flags = 0;
+        if let NicoType::Mint {
+            metadata,
+            current_token_account,
+            ..
+        } = &nico.nico_type
+        {
+            if *metadata != MetadataType::Token22Extension {
+                flags |= HAS_METADATA;
+                accounts.push(AccountMeta::new(metadata_address(&nico.pubkey), false));
+            }
+            if let Some(token_account_info) = current_token_account {
+                flags |= HAS_TOKEN_ACCOUNT;
+                accounts.push(AccountMeta::new(*token_account_info.key, false));
+            }
+        }
+        accounts.extend(
+            NicoAccountLayout::transfer(nico, recipient, &payer, None)
+                .unwrap()
+                .account_metas(),
+        );
+
+        self.process(accounts, vec![TRANSFER, flags], &[])
+            .await
+            .unwrap();
+    }
+
+    async fn assert_owner(&mut self, asset: &Pubkey, owner: &Pubkey) {
+        let nico = self.classify(asset, owner).await;
+        let asset_info = self.account_info(asset).await;
+        let mut remaining_accounts = vec![asset_info.clone()];
+        // pNFT token accounts are frozen, the token record says if locked
+        if let NicoType::Mint {
+            current_token_account: Some(token_account_info),
+            ..
+        } = &nico.nico_type
+        {
+            let token_record = token_record_address(asset, token_account_info.key);
+            remaining_accounts.push(self.account_info(&token_record).await.clone());
+        }
+        let remaining_accounts: &'static [AccountInfo<'static>] =
+            Box::leak(remaining_accounts.into_boxed_slice());
+
+        nico.assert_owned_by(owner, remaining_accounts).unwrap();
+    }
+}
+
+// Create an asset, classify it, transfer it and check the new owner.
+async fn round_trip(standard: NicoStandard, with_group: bool, assert_type: fn(&NicoType) -> bool) {
+    let mut harness = Harness::new(standard).await;
+    let payer = harness.payer();
+
+    let group = if with_group {
+        Some(harness.create(standard, None, CREATE_GROUP).await)
+    } else {
+        None
+    };
+    let asset = harness.create(standard, group, CREATE).await;
+
+    let nico = harness.classify(&asset, &payer).await;
+    assert!(assert_type(&nico.nico_type));
+    assert_eq!(nico.pubkey, asset);
+    assert_eq!(nico.group, group);
+
+    let recipient = Pubkey::new_unique();
+    harness.transfer(&nico, &recipient).await;
+    harness.assert_owner(&asset, &recipient).await;
+
+    // membership survives the transfer
+    assert_eq!(harness.classify(&asset, &recipient).await.group, group);
+}
+
+#[tokio::test]
+async fn nifty_asset() {
+    round_trip(NicoStandard::Nifty, false, |x| matches!(x, NicoType::Nifty)).await;
+}
+
+#[tokio::test]
+async fn nifty_asset_in_group() {
+    round_trip(NicoStandard::Nifty, true, |x| matches!(x, NicoType::Nifty)).await;
+}
+
+#[tokio::test]
+async fn core_asset() {
+    round_trip(NicoStandard::MxCore, false, |x| {
+        matches!(x, NicoType::MxCore)
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn core_asset_in_collection() {
+    round_trip(NicoStandard::MxCore, true, |x| {
+        matches!(x, NicoType::MxCore)
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn metaplex_nft() {
+    round_trip(NicoStandard::MxNonProgrammable, false, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::MxNonProgrammable,
+                ..
+            }
+        )
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn metaplex_nft_in_collection() {
+    round_trip(NicoStandard::MxNonProgrammable, true, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::MxNonProgrammable,
+                ..
+            }
+        )
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn metaplex_pnft() {
+    round_trip(NicoStandard::Mxprogrammable, false, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::Mxprogrammable,
+                ..
+            }
+        )
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn token_22_asset() {
+    round_trip(NicoStandard::Token22Extension, false, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::Token22Extension,
+                ..
+            }
+        )
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn token_22_asset_in_group() {
+    round_trip(NicoStandard::Token22Extension, true, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::Token22Extension,
+                ..
+            }
+        )
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn metaplex_pnft_in_collection() {
+    round_trip(NicoStandard::Mxprogrammable, true, |x| {
+        matches!(
+            x,
+            NicoType::Mint {
+                metadata: MetadataType::Mxprogrammable,
+                ..
+            }
+        )
+    })
+    .await;
+}
@@ -0,0 +1,157 @@
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use libreplex_nico::{metadata_address, MetadataType, Nico, NicoAccountLayout, NicoType};
+
+use crate::{accounts, instruction};
+
+// Instructions for the universal transfer program in this crate. Programs
+// calling it over CPI use the `cpi` module the `cpi` feature generates.
+
+// metadata, current owner and token account, the optional accounts the
+// program classifies a mint with
+fn mint_accounts(
+    nico: &Nico,
+    metadata_pointer: Option<Pubkey>,
+) -> (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>) {
+    match &nico.nico_type {
+        NicoType::Mint {
+            metadata,
+            current_owner,
+            current_token_account,
+        } => (
+            match metadata {
+                MetadataType::Token22Extension => metadata_pointer,
+                _ => Some(metadata_address(&nico.pubkey)),
+            },
+            current_owner.map(|x| *x.key),
+            current_token_account.map(|x| *x.key),
+        ),
+        _ => (None, None, None),
+    }
+}
+
+fn program_instruction(
+    mut accounts: Vec<AccountMeta>,
+    layout: NicoAccountLayout,
+    data: Vec<u8>,
+) -> Instruction {
+    accounts.extend(layout.account_metas());
+
+    Instruction {
+        program_id: crate::ID,
+        accounts,
+        data,
+    }
+}
+
+/// Transfer any Nico to `recipient`. `authority` signs for the asset and
+/// defaults to the payer. `metadata_pointer` is only read for Token-2022
+/// mints, see [`libreplex_nico::client::metadata_pointer`], and `rule_set`
+/// is as in [`libreplex_nico::client::transfer_remaining_accounts`].
+pub fn transfer_instruction(
+    nico: &Nico,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    authority: Option<Pubkey>,
+    metadata_pointer: Option<Pubkey>,
+    rule_set: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let (metadata, owner, token_account) = mint_accounts(nico, metadata_pointer);
+    let layout =
+        NicoAccountLayout::transfer(nico, recipient, &authority.unwrap_or(*payer), rule_set)?;
+
+    Ok(program_instruction(
+        accounts::NicoTransfer {
+            payer: *payer,
+            asset: nico.pubkey,
+            recipient: *recipient,
+            authority,
+            metadata,
+            owner,
+            token_account,
+        }
+        .to_account_metas(None),
+        layout,
+        instruction::Transfer {}.data(),
+    ))
+}
+
+/// Burn any Nico. `authority` defaults to the payer, `metadata_pointer` is
+/// as in [`transfer_instruction`].
+pub fn burn_instruction(
+    nico: &Nico,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    metadata_pointer: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let (metadata, owner, token_account) = mint_accounts(nico, metadata_pointer);
+
+    Ok(program_instruction(
+        accounts::NicoBurn {
+            payer: *payer,
+            asset: nico.pubkey,
+            authority,
+            metadata,
+            owner,
+            token_account,
+        }
+        .to_account_metas(None),
+        NicoAccountLayout::burn(nico)?,
+        instruction::Burn {}.data(),
+    ))
+}
+
+fn lock_accounts(
+    nico: &Nico,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    metadata_pointer: Option<Pubkey>,
+) -> Vec<AccountMeta> {
+    let (metadata, owner, token_account) = mint_accounts(nico, metadata_pointer);
+
+    accounts::NicoLock {
+        payer: *payer,
+        asset: nico.pubkey,
+        authority,
+        metadata,
+        owner,
+        token_account,
+    }
+    .to_account_metas(None)
+}
+
+/// Lock any Nico in place. `authority` defaults to the payer,
+/// `metadata_pointer` and `rule_set` are as in [`transfer_instruction`].
+pub fn lock_instruction(
+    nico: &Nico,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    metadata_pointer: Option<Pubkey>,
+    rule_set: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    Ok(program_instruction(
+        lock_accounts(nico, payer, authority, metadata_pointer),
+        NicoAccountLayout::lock(nico, rule_set)?,
+        instruction::Lock {}.data(),
+    ))
+}
+
+/// Unlock a Nico locked with [`lock_instruction`].
+pub fn unlock_instruction(
+    nico: &Nico,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    metadata_pointer: Option<Pubkey>,
+    rule_set: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    Ok(program_instruction(
+        lock_accounts(nico, payer, authority, metadata_pointer),
+        NicoAccountLayout::lock(nico, rule_set)?,
+        instruction::Unlock {}.data(),
+    ))
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::{classify, leak};
+
+#[derive(Accounts)]
+pub struct NicoBurn<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: classified as a Nico by the handler
+    #[account(mut)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// owner or delegate of the asset. the payer if not given
+    pub authority: Option<Signer<'info>>,
+
+    /// CHECK: token metadata account of a mint, checked by Nico
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: current owner of a mint. the authority if not given
+    pub owner: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: token account holding a mint, checked by Nico
+    #[account(mut)]
+    pub token_account: Option<UncheckedAccount<'info>>,
+}
+
+pub fn process_burn<'info>(ctx: Context<'_, '_, 'info, 'info, NicoBurn<'info>>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let authority = accounts.authority.as_deref();
+    let owner = accounts
+        .owner
+        .as_deref()
+        .or(authority)
+        .unwrap_or(&accounts.payer);
+
+    let nico = classify(
+        &accounts.asset,
+        accounts.metadata.as_deref(),
+        owner,
+        accounts.token_account.as_deref(),
+    )?;
+
+    nico.burn(
+        leak(&accounts.asset),
+        leak(&accounts.payer),
+        authority.map(leak),
+        ctx.remaining_accounts,
+        &[],
+    )?;
+
+    Ok(())
+}
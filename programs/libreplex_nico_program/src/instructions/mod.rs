@@ -0,0 +1,34 @@
+mod burn;
+pub use burn::*;
+
+mod lock;
+pub use lock::*;
+
+mod transfer;
+pub use transfer::*;
+
+use anchor_lang::prelude::*;
+
+use libreplex_nico::Nico;
+
+// Nico borrows its accounts for the whole instruction, longer than the
+// accounts struct can lend them. The bump allocator never frees, so leaking
+// a copy costs no more than the copy itself.
+pub(crate) fn leak<'info>(account: &AccountInfo<'info>) -> &'info AccountInfo<'info> {
+    Box::leak(Box::new(account.clone()))
+}
+
+pub(crate) fn classify<'info>(
+    asset: &AccountInfo<'info>,
+    metadata: Option<&AccountInfo<'info>>,
+    owner: &AccountInfo<'info>,
+    token_account: Option<&AccountInfo<'info>>,
+) -> Result<Nico<'info>> {
+    Nico::try_from_accounts(
+        leak(asset),
+        metadata.map(leak),
+        Some(leak(owner)),
+        token_account.map(leak),
+    )
+    .map_err(|error| ProgramError::from(error).into())
+}
@@ -0,0 +1,33 @@
+anchor_lang::declare_id!("Fxs3WYdX8uo1NTxxsxuWHZ2HFi4BAPph39pg8pjt2P2x");
+
+// the program macro expects the prelude at the crate root
+use anchor_lang::prelude::*;
+
+pub mod instructions;
+pub use instructions::*;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+// A universal transfer program: one instruction per operation that takes
+// any Nico, so clients can move assets without knowing their standard.
+#[anchor_lang::program]
+pub mod libreplex_nico_program {
+    use super::*;
+
+    pub fn transfer<'info>(ctx: Context<'_, '_, 'info, 'info, NicoTransfer<'info>>) -> Result<()> {
+        process_transfer(ctx)
+    }
+
+    pub fn burn<'info>(ctx: Context<'_, '_, 'info, 'info, NicoBurn<'info>>) -> Result<()> {
+        process_burn(ctx)
+    }
+
+    pub fn lock<'info>(ctx: Context<'_, '_, 'info, 'info, NicoLock<'info>>) -> Result<()> {
+        process_lock(ctx, true)
+    }
+
+    pub fn unlock<'info>(ctx: Context<'_, '_, 'info, 'info, NicoLock<'info>>) -> Result<()> {
+        process_lock(ctx, false)
+    }
+}
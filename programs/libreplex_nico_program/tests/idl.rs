@@ -0,0 +1,90 @@
+//! Checks `idl/libreplex_nico_program.json` against the IDL the `idl-build`
+//! feature generates from the accounts structs, so that the two cannot
+//! drift. To regenerate the file after changing the program, run
+//!
+//! ```sh
+//! UPDATE_IDL=1 cargo test -p libreplex_nico_program --features idl-build --test idl
+//! ```
+#![cfg(feature = "idl-build")]
+
+use std::{collections::HashMap, env, fs, path::Path};
+
+use anchor_lang::anchor_syn::idl::types::{
+    Idl, IdlAccountItem, IdlErrorCode, IdlInstruction, IdlTypeDefinition,
+};
+use libreplex_nico::Error;
+use libreplex_nico_program::{NicoBurn, NicoLock, NicoTransfer};
+use num_traits::FromPrimitive;
+
+// __anchor_private_gen_idl_accounts of an accounts struct
+type GenIdlAccounts = fn(
+    &mut HashMap<String, IdlTypeDefinition>,
+    &mut HashMap<String, IdlTypeDefinition>,
+) -> Vec<IdlAccountItem>;
+
+// the library errors the program returns, named as anchor names them
+fn errors() -> Vec<IdlErrorCode> {
+    (0..)
+        .map_while(Error::from_u32)
+        .enumerate()
+        .map(|(code, error)| {
+            let name = format!("{:?}", error);
+            IdlErrorCode {
+                code: code as u32,
+                name: name[..1].to_lowercase() + &name[1..],
+                msg: Some(error.to_string()),
+            }
+        })
+        .collect()
+}
+
+fn generated_idl() -> Idl {
+    let mut accounts = HashMap::new();
+    let mut defined_types = HashMap::new();
+
+    let mut instruction = |name: &str, gen_accounts: GenIdlAccounts| IdlInstruction {
+        name: name.into(),
+        docs: None,
+        accounts: gen_accounts(&mut accounts, &mut defined_types),
+        args: vec![],
+        returns: None,
+    };
+    let instructions = vec![
+        instruction("transfer", NicoTransfer::__anchor_private_gen_idl_accounts),
+        instruction("burn", NicoBurn::__anchor_private_gen_idl_accounts),
+        instruction("lock", NicoLock::__anchor_private_gen_idl_accounts),
+        instruction("unlock", NicoLock::__anchor_private_gen_idl_accounts),
+    ];
+
+    Idl {
+        version: env!("CARGO_PKG_VERSION").into(),
+        name: "libreplex_nico_program".into(),
+        docs: None,
+        constants: vec![],
+        instructions,
+        accounts: accounts.into_values().collect(),
+        types: defined_types.into_values().collect(),
+        events: None,
+        errors: Some(errors()),
+        metadata: Some(serde_json::json!({
+            "address": libreplex_nico_program::ID.to_string(),
+        })),
+    }
+}
+
+#[test]
+fn idl_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("idl/libreplex_nico_program.json");
+    let idl = serde_json::to_string_pretty(&generated_idl()).unwrap() + "\n";
+
+    if env::var_os("UPDATE_IDL").is_some() {
+        fs::write(&path, idl).unwrap();
+        return;
+    }
+
+    assert!(
+        fs::read_to_string(&path).unwrap() == idl,
+        "{} is out of date, regenerate it with UPDATE_IDL=1",
+        path.display()
+    );
+}